@@ -0,0 +1,87 @@
+use super::{Challenge, ChallengeSpec, InputOptions, Param, time_limit_secs};
+use crate::input_generators::gcd_numbers::{
+    generate_gcd_numbers, generate_gcd_numbers_large_capacity,
+};
+use crate::{reference_gcd, reference_gcd_large_capacity};
+use std::error::Error;
+
+/// Greatest common divisor of many pairs of numbers.
+pub struct Gcd {
+    spec: ChallengeSpec,
+}
+
+impl Gcd {
+    pub const EASY: Gcd = Gcd {
+        spec: ChallengeSpec {
+            name: "gcd",
+            version: 1,
+            generator_version: 1,
+            description: "GCD of many pairs of small numbers",
+            executables_dir: EXECUTABLES_DIR,
+            limits: time_limit_secs(5),
+            params: &[pairs(20_000), max_value(20_000)],
+            size_param: "pairs",
+            sources: &[],
+        },
+    };
+
+    pub const HARD: Gcd = Gcd {
+        spec: ChallengeSpec {
+            name: "gcd_hard",
+            version: 1,
+            generator_version: 1,
+            description: "GCD of many pairs of numbers up to u128::MAX",
+            executables_dir: EXECUTABLES_DIR,
+            limits: time_limit_secs(30),
+            params: &[pairs(1_000_000), max_value(u128::MAX)],
+            size_param: "pairs",
+            sources: &[],
+        },
+    };
+}
+
+const EXECUTABLES_DIR: &str = "./EXE_FILES_HERE/GREATEST_COMMON_DIVISOR";
+
+const fn pairs(default: u128) -> Param {
+    Param {
+        name: "pairs",
+        description: "Number of pairs (lines) in the input",
+        default,
+        min: 1,
+        max: 100_000_000,
+    }
+}
+
+const fn max_value(default: u128) -> Param {
+    Param {
+        name: "max_value",
+        description: "Largest value in a pair; values are drawn from 1..=max_value",
+        default,
+        min: 1,
+        max: u128::MAX,
+    }
+}
+
+impl Challenge for Gcd {
+    fn spec(&self) -> &ChallengeSpec {
+        &self.spec
+    }
+
+    fn generate_input(&self, options: &InputOptions) -> Result<String, Box<dyn Error>> {
+        let pairs = options.param(&self.spec, "pairs") as usize;
+        let max_value = options.param(&self.spec, "max_value");
+        match i64::try_from(max_value) {
+            Ok(max_value) => generate_gcd_numbers(pairs, max_value as u64, options.seed),
+            Err(_) => generate_gcd_numbers_large_capacity(pairs, max_value, options.seed),
+        }
+    }
+
+    fn expected_output(&self, input: &str, options: &InputOptions) -> String {
+        // The i64 reference is faster, but only takes values that fit in an i64.
+        if i64::try_from(options.param(&self.spec, "max_value")).is_ok() {
+            reference_gcd(input)
+        } else {
+            reference_gcd_large_capacity(input)
+        }
+    }
+}
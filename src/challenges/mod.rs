@@ -0,0 +1,249 @@
+//! The benchmark challenges and the registry that lists them.
+//!
+//! Adding a challenge means writing a type that implements [`Challenge`] in its own
+//! module and adding an instance of it to [`CHALLENGES`]. Each challenge is described
+//! by a [`ChallengeSpec`], which the runner, the command line and the README's
+//! parameter table all read from.
+
+pub mod gcd;
+pub mod obelisk;
+pub mod reverse;
+
+use crate::checker::Checker;
+use crate::input_generators::RNG_SEED;
+use crate::mismatch::first_mismatch;
+use crate::runner::Limits;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::time::Duration;
+
+/// How many tokens to show on either side of the first wrong token.
+const MISMATCH_CONTEXT: usize = 5;
+
+/// A size or range of a challenge's input that can be overridden on the command line.
+#[derive(Debug, Clone, Copy)]
+pub struct Param {
+    /// Name used on the command line, e.g. `pairs` in `--param pairs=1000`.
+    pub name: &'static str,
+    /// What the parameter controls.
+    pub description: &'static str,
+    pub default: u128,
+    /// Smallest accepted value.
+    pub min: u128,
+    /// Largest accepted value.
+    pub max: u128,
+}
+
+/// Everything about a challenge that is plain data.
+#[derive(Debug, Clone, Copy)]
+pub struct ChallengeSpec {
+    /// Name used on the command line, e.g. `gcd_hard`.
+    pub name: &'static str,
+    /// Bumped whenever the input format or the reference implementation changes the
+    /// expected output, so cached reference outputs from older versions are not reused.
+    pub version: u32,
+    /// Bumped whenever the input generator produces different input for the same seed
+    /// and parameters, so cached inputs from older generators are not reused.
+    pub generator_version: u32,
+    /// One-line description for `list`.
+    pub description: &'static str,
+    /// Directory that holds the submissions for this challenge.
+    pub executables_dir: &'static str,
+    /// Default resource limits for a single run.
+    pub limits: Limits,
+    /// Input parameters and their defaults.
+    pub params: &'static [Param],
+    /// The parameter that sets the input size, which `scale` grows by default.
+    pub size_param: &'static str,
+    /// Files the input generator reads, so cached inputs can be checked against them.
+    pub sources: &'static [&'static str],
+}
+
+impl ChallengeSpec {
+    /// Looks up a parameter by name.
+    pub fn param(&self, name: &str) -> Option<&Param> {
+        self.params.iter().find(|param| param.name == name)
+    }
+}
+
+/// Command-line knobs for input generation.
+#[derive(Debug, Clone)]
+pub struct InputOptions {
+    /// Seed for the input generator.
+    pub seed: u64,
+    /// Parameter values that override the challenge's defaults.
+    params: BTreeMap<&'static str, u128>,
+}
+
+impl Default for InputOptions {
+    fn default() -> Self {
+        InputOptions {
+            seed: RNG_SEED,
+            params: BTreeMap::new(),
+        }
+    }
+}
+
+impl InputOptions {
+    /// Overrides parameter `name` of `spec` after checking it exists and is in range.
+    pub fn set_param(
+        &mut self,
+        spec: &ChallengeSpec,
+        name: &str,
+        value: u128,
+    ) -> Result<(), String> {
+        let Some(param) = spec.param(name) else {
+            let names: Vec<&str> = spec.params.iter().map(|param| param.name).collect();
+            return Err(format!(
+                "{} has no parameter {name:?} (expected one of: {})",
+                spec.name,
+                names.join(", ")
+            ));
+        };
+        if !(param.min..=param.max).contains(&value) {
+            return Err(format!(
+                "{} must be in {}..={}, got {value}",
+                param.name,
+                format_param_value(param.min),
+                format_param_value(param.max)
+            ));
+        }
+        self.params.insert(param.name, value);
+        Ok(())
+    }
+
+    /// The value of parameter `name` of `spec`: the override if there is one, else the
+    /// default. Panics if `spec` has no such parameter.
+    pub fn param(&self, spec: &ChallengeSpec, name: &str) -> u128 {
+        let param = spec
+            .param(name)
+            .unwrap_or_else(|| panic!("{} has no parameter {name:?}", spec.name));
+        self.params.get(name).copied().unwrap_or(param.default)
+    }
+
+    /// Every parameter of `spec` with its value, overridden or default.
+    pub fn resolved_params(&self, spec: &ChallengeSpec) -> BTreeMap<String, u128> {
+        spec.params
+            .iter()
+            .map(|param| (param.name.to_string(), self.param(spec, param.name)))
+            .collect()
+    }
+}
+
+/// A coding challenge: how to produce its input, what the right answer is, where
+/// its submissions live and how they are judged.
+pub trait Challenge: Sync {
+    /// The challenge's name, limits and input parameters.
+    fn spec(&self) -> &ChallengeSpec;
+
+    /// Name used on the command line, e.g. `gcd_hard`.
+    fn name(&self) -> &'static str {
+        self.spec().name
+    }
+
+    /// One-line description for `list`.
+    fn description(&self) -> &'static str {
+        self.spec().description
+    }
+
+    /// Produces the input that is piped to every submission.
+    fn generate_input(&self, options: &InputOptions) -> Result<String, Box<dyn Error>>;
+
+    /// Computes the correct output for `input`, generated with `options`, with the
+    /// reference implementation.
+    fn expected_output(&self, input: &str, options: &InputOptions) -> String;
+
+    /// Directory that holds the submissions for this challenge.
+    fn executables_dir(&self) -> &'static str {
+        self.spec().executables_dir
+    }
+
+    /// How submission output is compared to the expected output.
+    fn checker(&self) -> Checker {
+        Checker::Tokens
+    }
+
+    /// Default resource limits for a single run.
+    fn limits(&self) -> Limits {
+        self.spec().limits
+    }
+
+    /// Explains to the author how `output` differs from `expected_output`.
+    fn explain_wrong_answer(&self, expected_output: &str, output: &str) -> String {
+        match first_mismatch(expected_output, output, MISMATCH_CONTEXT) {
+            Some(mismatch) => mismatch.to_string(),
+            None => String::from("All tokens match, but the whitespace between them differs."),
+        }
+    }
+}
+
+/// Every challenge, in the order they are listed.
+pub static CHALLENGES: &[&dyn Challenge] = &[
+    &reverse::Reverse::EASY,
+    &reverse::Reverse::LARGE,
+    &gcd::Gcd::EASY,
+    &gcd::Gcd::HARD,
+    &obelisk::Obelisk::EASY,
+    &obelisk::Obelisk::HARD,
+];
+
+/// Looks up a challenge by its command-line name.
+pub fn find_challenge(name: &str) -> Option<&'static dyn Challenge> {
+    CHALLENGES
+        .iter()
+        .copied()
+        .find(|challenge| challenge.name() == name)
+}
+
+/// Renders the parameters of every challenge as the Markdown table in the README.
+pub fn params_markdown() -> String {
+    let mut markdown = String::from(
+        "| Challenge | Parameter | Default | Allowed | Description |\n\
+         | --- | --- | --- | --- | --- |\n",
+    );
+    for challenge in CHALLENGES {
+        for param in challenge.spec().params {
+            markdown.push_str(&format!(
+                "| `{}` | `{}` | {} | {}..={} | {} |\n",
+                challenge.name(),
+                param.name,
+                format_param_value(param.default),
+                format_param_value(param.min),
+                format_param_value(param.max),
+                param.description
+            ));
+        }
+    }
+    markdown
+}
+
+/// Formats a parameter value with thousands separators, e.g. `20,000`, or as
+/// `u128::MAX`.
+pub fn format_param_value(value: u128) -> String {
+    if value == u128::MAX {
+        return String::from("u128::MAX");
+    }
+    let digits = value.to_string();
+    let mut formatted = String::with_capacity(digits.len() * 4 / 3);
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            formatted.push(',');
+        }
+        formatted.push(digit);
+    }
+    formatted
+}
+
+/// Seed for run `run` (1-based) when every run gets fresh input: consecutive seeds
+/// starting at `base_seed`, so a whole session can be reproduced from its base seed.
+pub fn seed_for_run(base_seed: u64, run: u32) -> u64 {
+    base_seed.wrapping_add(u64::from(run.saturating_sub(1)))
+}
+
+/// Limits with the given time limit in seconds and no memory limit.
+const fn time_limit_secs(secs: u64) -> Limits {
+    Limits {
+        time: Duration::from_secs(secs),
+        memory_bytes: None,
+    }
+}
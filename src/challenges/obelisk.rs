@@ -0,0 +1,146 @@
+use super::{Challenge, ChallengeSpec, InputOptions, Param, time_limit_secs};
+use crate::checker::Checker;
+use crate::input_generators::obelisk::{RANDOM_STONE_COUNT, RANDOM_STONE_RANGE, gen_obelisks};
+use crate::mismatch::{IntegerAnswer, compare_integer_answer};
+use crate::reference_obelisk_count;
+use num_bigint::Sign;
+use std::error::Error;
+
+/// Count the obelisks after pulling the lever a number of times.
+pub struct Obelisk {
+    spec: ChallengeSpec,
+}
+
+impl Obelisk {
+    pub const EASY: Obelisk = Obelisk {
+        spec: ChallengeSpec {
+            name: "obelisk",
+            version: 1,
+            generator_version: 1,
+            description: "Count the obelisks after pulling the lever a few times",
+            executables_dir: "./EXE_FILES_HERE/OBELISK_EASY",
+            limits: time_limit_secs(10),
+            params: &[pulls(25), RANDOM_STONES, STONE_MIN, STONE_MAX],
+            size_param: "pulls",
+            sources: &[],
+        },
+    };
+
+    pub const HARD: Obelisk = Obelisk {
+        spec: ChallengeSpec {
+            name: "obelisk_hard",
+            version: 1,
+            generator_version: 1,
+            description: "Count the obelisks after pulling the lever many times",
+            executables_dir: "./EXE_FILES_HERE/OBELISK_HARD",
+            limits: time_limit_secs(30),
+            params: &[pulls(100), RANDOM_STONES, STONE_MIN, STONE_MAX],
+            size_param: "pulls",
+            sources: &[],
+        },
+    };
+}
+
+const fn pulls(default: u128) -> Param {
+    Param {
+        name: "pulls",
+        description: "Number of lever pulls",
+        default,
+        min: 0,
+        max: 150,
+    }
+}
+
+const RANDOM_STONES: Param = Param {
+    name: "random_stones",
+    description: "Random stones added to the fixed ones",
+    default: RANDOM_STONE_COUNT as u128,
+    min: 0,
+    max: 1_000_000,
+};
+
+const STONE_MIN: Param = Param {
+    name: "stone_min",
+    description: "Smallest random stone",
+    default: *RANDOM_STONE_RANGE.start(),
+    min: 0,
+    max: 1_000_000_000,
+};
+
+const STONE_MAX: Param = Param {
+    name: "stone_max",
+    description: "Largest random stone",
+    default: *RANDOM_STONE_RANGE.end(),
+    min: 0,
+    max: 1_000_000_000,
+};
+
+impl Challenge for Obelisk {
+    fn spec(&self) -> &ChallengeSpec {
+        &self.spec
+    }
+
+    fn generate_input(&self, options: &InputOptions) -> Result<String, Box<dyn Error>> {
+        let stone_min = options.param(&self.spec, "stone_min");
+        let stone_max = options.param(&self.spec, "stone_max");
+        if stone_min > stone_max {
+            return Err(
+                format!("stone_min ({stone_min}) is larger than stone_max ({stone_max})").into(),
+            );
+        }
+        Ok(gen_obelisks(
+            options.seed,
+            options.param(&self.spec, "random_stones") as usize,
+            stone_min..=stone_max,
+        ))
+    }
+
+    fn expected_output(&self, input: &str, options: &InputOptions) -> String {
+        let obelisks: Vec<u128> = input
+            .split(" ")
+            .map(|obelisk| obelisk.parse::<u128>().unwrap())
+            .collect();
+        let pulls = options.param(&self.spec, "pulls") as usize;
+        reference_obelisk_count(&obelisks, pulls).to_string()
+    }
+
+    fn checker(&self) -> Checker {
+        Checker::Integer
+    }
+
+    fn explain_wrong_answer(&self, expected_output: &str, output: &str) -> String {
+        match compare_integer_answer(expected_output, output) {
+            Some(IntegerAnswer::Number { difference, .. }) if difference.sign() == Sign::NoSign => {
+                format!(
+                    "The provided answer has the right value but is written differently: {:?} \
+                     instead of {:?}.",
+                    output.trim(),
+                    expected_output.trim()
+                )
+            }
+            Some(IntegerAnswer::Number { difference, ratio }) => {
+                let direction = if difference.sign() == Sign::Minus {
+                    "low"
+                } else {
+                    "high"
+                };
+                format!(
+                    "The provided answer was too {} (off by {:+}, {}x the expected answer).",
+                    direction,
+                    difference,
+                    format_ratio(ratio)
+                )
+            }
+            _ => format!("The provided answer is not a number: {:?}", output.trim()),
+        }
+    }
+}
+
+/// Formats a ratio with fixed decimals, switching to scientific notation when extreme.
+fn format_ratio(ratio: f64) -> String {
+    if (1e-3..1e4).contains(&ratio.abs()) {
+        format!("{:.4}", ratio)
+    } else {
+        format!("{:.3e}", ratio)
+    }
+}
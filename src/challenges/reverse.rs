@@ -0,0 +1,65 @@
+use super::{Challenge, ChallengeSpec, InputOptions, Param, time_limit_secs};
+use crate::input_generators::wordlist::{WORD_SOURCE, wordlist_seeded};
+use crate::reference_reverse;
+use std::error::Error;
+
+/// Reverse every word in a long list of words.
+pub struct Reverse {
+    spec: ChallengeSpec,
+}
+
+impl Reverse {
+    pub const EASY: Reverse = Reverse {
+        spec: ChallengeSpec {
+            name: "reverse",
+            version: 1,
+            generator_version: 1,
+            description: "Reverse each word in a list of words",
+            executables_dir: EXECUTABLES_DIR,
+            limits: time_limit_secs(5),
+            params: &[words(20_000)],
+            size_param: "words",
+            sources: &[WORD_SOURCE],
+        },
+    };
+
+    pub const LARGE: Reverse = Reverse {
+        spec: ChallengeSpec {
+            name: "reverse_large",
+            version: 1,
+            generator_version: 1,
+            description: "Reverse each word in a very long list of words",
+            executables_dir: EXECUTABLES_DIR,
+            limits: time_limit_secs(60),
+            params: &[words(20_000_000)],
+            size_param: "words",
+            sources: &[WORD_SOURCE],
+        },
+    };
+}
+
+const EXECUTABLES_DIR: &str = "./EXE_FILES_HERE/REVERSE_STRING";
+
+const fn words(default: u128) -> Param {
+    Param {
+        name: "words",
+        description: "Number of words in the input",
+        default,
+        min: 1,
+        max: 100_000_000,
+    }
+}
+
+impl Challenge for Reverse {
+    fn spec(&self) -> &ChallengeSpec {
+        &self.spec
+    }
+
+    fn generate_input(&self, options: &InputOptions) -> Result<String, Box<dyn Error>> {
+        wordlist_seeded(options.param(&self.spec, "words") as usize, options.seed)
+    }
+
+    fn expected_output(&self, input: &str, _options: &InputOptions) -> String {
+        reference_reverse(input)
+    }
+}
@@ -0,0 +1,201 @@
+use crate::mismatch::{IntegerAnswer, compare_integer_answer};
+use crate::runner::Verdict;
+
+/// Decides whether a submission's output is correct.
+///
+/// Each challenge picks the checker that matches how strictly its output format
+/// is specified, so submissions are judged on content rather than formatting.
+#[derive(Debug, Clone, Copy)]
+pub enum Checker {
+    /// Identical after trimming leading and trailing whitespace.
+    Exact,
+    /// A single integer, identical to the expected one after trimming. Output that is
+    /// not an integer at all is judged [`Verdict::NotANumber`] instead of a wrong answer.
+    Integer,
+    /// The same sequence of tokens, however they are separated by ASCII whitespace.
+    /// Tokens are split the same way when judged as a stream, see [`crate::digest`].
+    Tokens,
+    /// The same lines, ignoring trailing whitespace on each line and blank lines at the end.
+    Lines,
+    /// The same number of tokens, each numerically equal to the expected one within
+    /// an absolute or relative tolerance. An expected NaN or infinity is only matched
+    /// by the same, and non-numeric tokens must match exactly.
+    Numeric {
+        absolute_tolerance: f64,
+        relative_tolerance: f64,
+    },
+    /// A custom validator called with `(input, expected_output, output)`.
+    Custom(fn(&str, &str, &str) -> bool),
+}
+
+impl Checker {
+    /// Returns whether `output` is an accepted answer for `input`.
+    pub fn check(&self, input: &str, expected_output: &str, output: &str) -> bool {
+        match *self {
+            Checker::Exact | Checker::Integer => output.trim() == expected_output.trim(),
+            Checker::Tokens => output
+                .split_ascii_whitespace()
+                .eq(expected_output.split_ascii_whitespace()),
+            Checker::Lines => significant_lines(output).eq(significant_lines(expected_output)),
+            Checker::Numeric {
+                absolute_tolerance,
+                relative_tolerance,
+            } => {
+                let mut expected_tokens = expected_output.split_ascii_whitespace();
+                let mut tokens = output.split_ascii_whitespace();
+                loop {
+                    match (expected_tokens.next(), tokens.next()) {
+                        (None, None) => return true,
+                        (Some(expected), Some(actual)) => {
+                            let close = match (expected.parse::<f64>(), actual.parse::<f64>()) {
+                                (Ok(e), Ok(a)) => {
+                                    numbers_close(e, a, absolute_tolerance, relative_tolerance)
+                                }
+                                _ => expected == actual,
+                            };
+                            if !close {
+                                return false;
+                            }
+                        }
+                        _ => return false,
+                    }
+                }
+            }
+            Checker::Custom(validate) => validate(input, expected_output, output),
+        }
+    }
+}
+
+impl Checker {
+    /// Judges `output` for `input`: accepted, a wrong answer or, for
+    /// [`Checker::Integer`], not a number.
+    pub fn verdict(&self, input: &str, expected_output: &str, output: &str) -> Verdict {
+        if self.check(input, expected_output, output) {
+            return Verdict::Accepted;
+        }
+        match (self, compare_integer_answer(expected_output, output)) {
+            (Checker::Integer, Some(IntegerAnswer::NotANumber)) => Verdict::NotANumber,
+            _ => Verdict::WrongAnswer,
+        }
+    }
+}
+
+/// Whether `actual` is within the tolerances of `expected`. NaN only matches NaN and
+/// an infinity only the same infinity, whatever the tolerances.
+fn numbers_close(
+    expected: f64,
+    actual: f64,
+    absolute_tolerance: f64,
+    relative_tolerance: f64,
+) -> bool {
+    if expected.is_nan() || actual.is_nan() {
+        return expected.is_nan() && actual.is_nan();
+    }
+    if expected.is_infinite() || actual.is_infinite() {
+        return expected == actual;
+    }
+    let diff = (actual - expected).abs();
+    diff <= absolute_tolerance || diff <= relative_tolerance * expected.abs()
+}
+
+/// Lines of `text` with trailing whitespace removed, without trailing blank lines.
+fn significant_lines(text: &str) -> impl Iterator<Item = &str> {
+    text.trim_end().lines().map(str::trim_end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NUMERIC: Checker = Checker::Numeric {
+        absolute_tolerance: 1e-6,
+        relative_tolerance: 1e-9,
+    };
+
+    #[test]
+    fn exact_ignores_only_surrounding_whitespace() {
+        assert!(Checker::Exact.check("", "a b\n", "  a b  \n\n"));
+        assert!(!Checker::Exact.check("", "a b", "a  b"));
+        assert!(!Checker::Exact.check("", "a\nb", "a b"));
+    }
+
+    #[test]
+    fn integer_compares_the_trimmed_answer() {
+        assert!(Checker::Integer.check("", "42\n", " 42 "));
+        assert!(!Checker::Integer.check("", "42", "43"));
+        assert!(!Checker::Integer.check("", "42", "042"));
+    }
+
+    #[test]
+    fn tokens_ignore_how_they_are_separated() {
+        assert!(Checker::Tokens.check("", "a b\nc", "a\tb  c\n"));
+        assert!(!Checker::Tokens.check("", "a b c", "a b"));
+        assert!(!Checker::Tokens.check("", "a b", "b a"));
+        // A non-breaking space is not a separator.
+        assert!(!Checker::Tokens.check("", "a b", "a\u{a0}b"));
+    }
+
+    #[test]
+    fn lines_ignore_trailing_whitespace_and_blank_lines() {
+        assert!(Checker::Lines.check("", "a b\nc", "a b  \nc\n\n"));
+        assert!(!Checker::Lines.check("", "a b\nc", "a b c"));
+        assert!(!Checker::Lines.check("", "a\nb", "a\n\nb"));
+        assert!(!Checker::Lines.check("", "a", " a"));
+    }
+
+    #[test]
+    fn numeric_accepts_values_within_tolerance() {
+        assert!(NUMERIC.check("", "1.5 2", "1.5000001 2.0"));
+        assert!(NUMERIC.check("", "1e12", "1000000000000.0005"));
+        assert!(!NUMERIC.check("", "1.5", "1.51"));
+        assert!(!NUMERIC.check("", "1 2", "1"));
+        assert!(!NUMERIC.check("", "1", "1 2"));
+    }
+
+    #[test]
+    fn numeric_compares_other_tokens_exactly() {
+        assert!(NUMERIC.check("", "yes 1.0", "yes 1"));
+        assert!(!NUMERIC.check("", "yes 1.0", "Yes 1"));
+        assert!(!NUMERIC.check("", "1.0", "one"));
+    }
+
+    #[test]
+    fn numeric_matches_nan_and_infinity_only_with_themselves() {
+        assert!(NUMERIC.check("", "nan", "NaN"));
+        assert!(NUMERIC.check("", "inf -inf", "inf -infinity"));
+        assert!(!NUMERIC.check("", "nan", "0"));
+        assert!(!NUMERIC.check("", "0", "nan"));
+        assert!(!NUMERIC.check("", "inf", "-inf"));
+        assert!(!NUMERIC.check("", "1e300", "inf"));
+        let lenient = Checker::Numeric {
+            absolute_tolerance: f64::INFINITY,
+            relative_tolerance: 0.0,
+        };
+        assert!(!lenient.check("", "inf", "1"));
+        assert!(!lenient.check("", "1", "nan"));
+    }
+
+    #[test]
+    fn custom_calls_the_validator() {
+        let checker = Checker::Custom(|input, _, output| output.trim() == input.trim());
+        assert!(checker.check("7", "", "7\n"));
+        assert!(!checker.check("7", "7", "8"));
+    }
+
+    #[test]
+    fn verdict_is_not_a_number_only_for_the_integer_checker() {
+        assert_eq!(Checker::Integer.verdict("", "42", "42"), Verdict::Accepted);
+        assert_eq!(
+            Checker::Integer.verdict("", "42", "43"),
+            Verdict::WrongAnswer
+        );
+        assert_eq!(
+            Checker::Integer.verdict("", "42", "abc"),
+            Verdict::NotANumber
+        );
+        for checker in [Checker::Exact, Checker::Tokens, Checker::Lines, NUMERIC] {
+            assert_eq!(checker.verdict("", "42", "42"), Verdict::Accepted);
+            assert_eq!(checker.verdict("", "42", "abc"), Verdict::WrongAnswer);
+        }
+    }
+}
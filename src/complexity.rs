@@ -0,0 +1,347 @@
+//! Empirical complexity of measurements taken at several input sizes.
+//!
+//! Two estimates are made. [`fit_power_law`] fits `value = c * n^k` on a log-log
+//! scale, giving a free exponent `k`. [`best_fit`] fits `value = a + c * f(n)` for
+//! each common complexity class `f` and picks the slowest-growing one whose relative
+//! error is close to the smallest; the intercept `a` absorbs fixed costs such as
+//! process startup, which would otherwise flatten the curve at small sizes.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// A class fits about as well as the best one if its relative error is at most this
+/// much larger, relatively and absolutely; measurements are never exact, and a
+/// slower-growing class that explains them as well is the better answer.
+const FIT_TOLERANCE_FACTOR: f64 = 1.1;
+const FIT_TOLERANCE_ABSOLUTE: f64 = 0.01;
+
+/// Growth that adds less than this fraction of the largest value across the measured
+/// sizes is indistinguishable from noise, so the fit is reported as constant.
+const MIN_SIGNIFICANT_GROWTH: f64 = 0.1;
+
+/// `value ≈ coefficient * n^exponent`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PowerFit {
+    pub exponent: f64,
+    pub coefficient: f64,
+    /// Coefficient of determination of the log-log regression.
+    pub r_squared: f64,
+}
+
+/// Least-squares fit of `ln value = ln coefficient + exponent * ln n`. `None` unless
+/// there are at least two distinct sizes and every value is positive.
+pub fn fit_power_law(sizes: &[f64], values: &[f64]) -> Option<PowerFit> {
+    if sizes.len() != values.len() || values.iter().any(|&value| value <= 0.0) {
+        return None;
+    }
+    let xs: Vec<f64> = sizes.iter().map(|size| size.ln()).collect();
+    let ys: Vec<f64> = values.iter().map(|value| value.ln()).collect();
+    let count = xs.len() as f64;
+    let mean_x = xs.iter().sum::<f64>() / count;
+    let mean_y = ys.iter().sum::<f64>() / count;
+    let sxx: f64 = xs.iter().map(|x| (x - mean_x).powi(2)).sum();
+    let sxy: f64 = xs
+        .iter()
+        .zip(&ys)
+        .map(|(x, y)| (x - mean_x) * (y - mean_y))
+        .sum();
+    let syy: f64 = ys.iter().map(|y| (y - mean_y).powi(2)).sum();
+    if sxx <= 0.0 {
+        return None;
+    }
+    let exponent = sxy / sxx;
+    Some(PowerFit {
+        exponent,
+        coefficient: (mean_y - exponent * mean_x).exp(),
+        r_squared: if syy > 0.0 {
+            sxy * sxy / (sxx * syy)
+        } else {
+            1.0
+        },
+    })
+}
+
+/// Common complexity classes, from slowest- to fastest-growing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Complexity {
+    Constant,
+    Logarithmic,
+    Linear,
+    Linearithmic,
+    Quadratic,
+    QuadraticLog,
+    Cubic,
+    Exponential,
+}
+
+impl Complexity {
+    pub const ALL: [Complexity; 8] = [
+        Complexity::Constant,
+        Complexity::Logarithmic,
+        Complexity::Linear,
+        Complexity::Linearithmic,
+        Complexity::Quadratic,
+        Complexity::QuadraticLog,
+        Complexity::Cubic,
+        Complexity::Exponential,
+    ];
+
+    /// The growth function at size `n`.
+    pub fn eval(self, n: f64) -> f64 {
+        // Keep logarithms positive at the smallest sizes.
+        let log = n.max(2.0).ln();
+        match self {
+            Complexity::Constant => 1.0,
+            Complexity::Logarithmic => log,
+            Complexity::Linear => n,
+            Complexity::Linearithmic => n * log,
+            Complexity::Quadratic => n * n,
+            Complexity::QuadraticLog => n * n * log,
+            Complexity::Cubic => n * n * n,
+            Complexity::Exponential => n.exp2(),
+        }
+    }
+}
+
+impl fmt::Display for Complexity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let notation = match self {
+            Complexity::Constant => "O(1)",
+            Complexity::Logarithmic => "O(log n)",
+            Complexity::Linear => "O(n)",
+            Complexity::Linearithmic => "O(n log n)",
+            Complexity::Quadratic => "O(n^2)",
+            Complexity::QuadraticLog => "O(n^2 log n)",
+            Complexity::Cubic => "O(n^3)",
+            Complexity::Exponential => "O(2^n)",
+        };
+        f.write_str(notation)
+    }
+}
+
+/// `value ≈ intercept + coefficient * complexity(n)`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ClassFit {
+    pub complexity: Complexity,
+    pub intercept: f64,
+    pub coefficient: f64,
+    /// Root mean square of the residuals relative to the measured values.
+    pub relative_error: f64,
+}
+
+/// The complexity class that best explains `values` at `sizes`, or `None` without at
+/// least three measurements. Each class is fitted with an intercept, weighting
+/// residuals by the measured value so small sizes count as much as large ones, and
+/// the slowest-growing class that fits about as well as the best one wins.
+pub fn best_fit(sizes: &[f64], values: &[f64]) -> Option<ClassFit> {
+    if sizes.len() != values.len() || sizes.len() < 3 || values.iter().any(|&v| v <= 0.0) {
+        return None;
+    }
+    let fits: Vec<ClassFit> = Complexity::ALL
+        .into_iter()
+        .filter_map(|complexity| fit_class(complexity, sizes, values))
+        .collect();
+    let smallest_error = fits
+        .iter()
+        .map(|fit| fit.relative_error)
+        .fold(f64::INFINITY, f64::min);
+    let tolerance = smallest_error * FIT_TOLERANCE_FACTOR + FIT_TOLERANCE_ABSOLUTE;
+    // `Complexity::ALL` is ordered by growth, so the first close fit is the simplest.
+    let fit = fits
+        .into_iter()
+        .find(|fit| fit.relative_error <= tolerance)?;
+    if fit.complexity != Complexity::Constant && !grows_significantly(&fit, sizes, values) {
+        return fit_class(Complexity::Constant, sizes, values);
+    }
+    Some(fit)
+}
+
+/// Whether the growth term of `fit` adds a noticeable amount over `sizes`.
+fn grows_significantly(fit: &ClassFit, sizes: &[f64], values: &[f64]) -> bool {
+    let growth: Vec<f64> = sizes.iter().map(|&n| fit.complexity.eval(n)).collect();
+    let smallest = growth.iter().copied().fold(f64::INFINITY, f64::min);
+    let largest = growth.iter().copied().fold(0.0, f64::max);
+    let largest_value = values.iter().copied().fold(0.0, f64::max);
+    fit.coefficient * (largest - smallest) >= MIN_SIGNIFICANT_GROWTH * largest_value
+}
+
+/// Weighted least-squares fit of `value = intercept + coefficient * complexity(n)`
+/// with both terms kept non-negative.
+fn fit_class(complexity: Complexity, sizes: &[f64], values: &[f64]) -> Option<ClassFit> {
+    let growth: Vec<f64> = sizes.iter().map(|&n| complexity.eval(n)).collect();
+    if growth.iter().any(|g| !g.is_finite()) {
+        return None;
+    }
+    let weights: Vec<f64> = values.iter().map(|value| 1.0 / (value * value)).collect();
+    let sum = |term: &dyn Fn(usize) -> f64| (0..values.len()).map(term).sum::<f64>();
+    let sw = sum(&|i| weights[i]);
+    let swg = sum(&|i| weights[i] * growth[i]);
+    let swgg = sum(&|i| weights[i] * growth[i] * growth[i]);
+    let swv = sum(&|i| weights[i] * values[i]);
+    let swgv = sum(&|i| weights[i] * growth[i] * values[i]);
+
+    let determinant = sw * swgg - swg * swg;
+    let (mut intercept, mut coefficient) = if complexity == Complexity::Constant {
+        (swv / sw, 0.0)
+    } else if determinant.abs() > f64::EPSILON * sw * swgg {
+        (
+            (swgg * swv - swg * swgv) / determinant,
+            (sw * swgv - swg * swv) / determinant,
+        )
+    } else {
+        (0.0, swgv / swgg)
+    };
+    if intercept < 0.0 {
+        intercept = 0.0;
+        coefficient = swgv / swgg;
+    }
+    if coefficient < 0.0 {
+        intercept = swv / sw;
+        coefficient = 0.0;
+    }
+
+    let squared_error = sum(&|i| {
+        let residual = values[i] - intercept - coefficient * growth[i];
+        weights[i] * residual * residual
+    });
+    Some(ClassFit {
+        complexity,
+        intercept,
+        coefficient,
+        relative_error: (squared_error / values.len() as f64).sqrt(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+        assert!(
+            (actual - expected).abs() <= tolerance,
+            "expected {expected} ± {tolerance}, got {actual}"
+        );
+    }
+
+    /// Input sizes doubling from 1,000 to 64,000.
+    fn doubling_sizes() -> Vec<f64> {
+        (0..7).map(|i| 1000.0 * 2f64.powi(i)).collect()
+    }
+
+    /// Bit sizes from 8 to 20, as for challenges scaled by the width of their numbers.
+    fn bit_sizes() -> Vec<f64> {
+        (4..=10).map(|i| 2.0 * i as f64).collect()
+    }
+
+    /// `intercept + coefficient * complexity(n)` at every size.
+    fn synthetic(
+        complexity: Complexity,
+        sizes: &[f64],
+        intercept: f64,
+        coefficient: f64,
+    ) -> Vec<f64> {
+        sizes
+            .iter()
+            .map(|&n| intercept + coefficient * complexity.eval(n))
+            .collect()
+    }
+
+    #[test]
+    fn power_law_recovers_exponent_and_coefficient() {
+        let sizes = doubling_sizes();
+        let values: Vec<f64> = sizes.iter().map(|n| 3.0 * n * n).collect();
+        let fit = fit_power_law(&sizes, &values).unwrap();
+        assert_close(fit.exponent, 2.0, 1e-9);
+        assert_close(fit.coefficient, 3.0, 1e-6);
+        assert_close(fit.r_squared, 1.0, 1e-9);
+    }
+
+    #[test]
+    fn power_law_of_constant_values_has_zero_exponent() {
+        let sizes = doubling_sizes();
+        let fit = fit_power_law(&sizes, &vec![0.5; sizes.len()]).unwrap();
+        assert_close(fit.exponent, 0.0, 1e-12);
+        assert_close(fit.coefficient, 0.5, 1e-12);
+    }
+
+    #[test]
+    fn power_law_needs_distinct_sizes_and_positive_values() {
+        assert_eq!(fit_power_law(&[10.0, 10.0], &[1.0, 2.0]), None);
+        assert_eq!(fit_power_law(&[10.0, 20.0], &[1.0, 0.0]), None);
+        assert_eq!(fit_power_law(&[10.0, 20.0], &[1.0]), None);
+    }
+
+    #[test]
+    fn class_fit_recovers_intercept_and_coefficient() {
+        let sizes = doubling_sizes();
+        let values = synthetic(Complexity::Linear, &sizes, 0.02, 1e-6);
+        let fit = fit_class(Complexity::Linear, &sizes, &values).unwrap();
+        assert_close(fit.intercept, 0.02, 1e-9);
+        assert_close(fit.coefficient, 1e-6, 1e-12);
+        assert_close(fit.relative_error, 0.0, 1e-9);
+    }
+
+    #[test]
+    fn class_fit_keeps_intercept_and_coefficient_non_negative() {
+        let sizes = doubling_sizes();
+        // Linear data with a negative intercept, and data that shrinks with size.
+        let values = synthetic(Complexity::Linear, &sizes, -0.0005, 1e-6);
+        let fit = fit_class(Complexity::Linear, &sizes, &values).unwrap();
+        assert_eq!(fit.intercept, 0.0);
+        let values: Vec<f64> = sizes.iter().map(|n| 1.0 / n).collect();
+        let fit = fit_class(Complexity::Linear, &sizes, &values).unwrap();
+        assert_eq!(fit.coefficient, 0.0);
+        assert!(fit.intercept > 0.0);
+    }
+
+    #[test]
+    fn class_fit_skips_growth_that_overflows() {
+        assert_eq!(
+            fit_class(Complexity::Exponential, &[10.0, 2000.0], &[1.0, 2.0]),
+            None
+        );
+    }
+
+    #[test]
+    fn best_fit_finds_each_class() {
+        let sizes = doubling_sizes();
+        for (complexity, coefficient) in [
+            (Complexity::Linear, 1e-6),
+            (Complexity::Linearithmic, 1e-7),
+            (Complexity::Quadratic, 1e-10),
+        ] {
+            let values = synthetic(complexity, &sizes, 0.002, coefficient);
+            assert_eq!(best_fit(&sizes, &values).unwrap().complexity, complexity);
+        }
+        let sizes = bit_sizes();
+        let values = synthetic(Complexity::Exponential, &sizes, 0.002, 1e-6);
+        assert_eq!(
+            best_fit(&sizes, &values).unwrap().complexity,
+            Complexity::Exponential
+        );
+    }
+
+    #[test]
+    fn best_fit_of_flat_data_is_constant() {
+        for sizes in [doubling_sizes(), bit_sizes()] {
+            let flat = vec![0.002; sizes.len()];
+            // Timing noise of a few percent around the same value.
+            let noisy: Vec<f64> = (0..sizes.len())
+                .map(|i| 0.002 * [1.0, 1.03, 0.98, 1.01, 0.97, 1.02, 1.0][i])
+                .collect();
+            // A tiny trend is not significant either.
+            let drifting = synthetic(Complexity::Linear, &sizes, 0.002, 1e-9);
+            for values in [flat, noisy, drifting] {
+                let fit = best_fit(&sizes, &values).unwrap();
+                assert_eq!(fit.complexity, Complexity::Constant, "{values:?}");
+                assert_eq!(fit.coefficient, 0.0);
+            }
+        }
+    }
+
+    #[test]
+    fn best_fit_needs_three_positive_values() {
+        assert_eq!(best_fit(&[1.0, 2.0], &[1.0, 2.0]), None);
+        assert_eq!(best_fit(&[1.0, 2.0, 3.0], &[1.0, 0.0, 3.0]), None);
+    }
+}
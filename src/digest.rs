@@ -0,0 +1,294 @@
+//! Chunked digests of whitespace-separated tokens, for judging huge outputs without
+//! holding them in memory.
+//!
+//! Output is normalised to its tokens, as [`Checker::Tokens`](crate::Checker::Tokens)
+//! sees it, and every [`CHUNK_TOKENS`] tokens are hashed separately, so a mismatch
+//! can be located without keeping either output around.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::io::{self, Write};
+
+/// How many tokens each chunk of a [`ChunkedDigest`] covers.
+pub const CHUNK_TOKENS: usize = 1 << 16;
+
+/// SHA-256 of every chunk of tokens in an output.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChunkedDigest {
+    /// Tokens per chunk; the last chunk may hold fewer.
+    pub chunk_tokens: usize,
+    pub token_count: u64,
+    /// Hex-encoded SHA-256 of each chunk.
+    pub chunks: Vec<String>,
+}
+
+impl ChunkedDigest {
+    /// Digests the tokens of `text`.
+    pub fn of(text: &str) -> Self {
+        Self::with_chunk_tokens(text, CHUNK_TOKENS)
+    }
+
+    fn with_chunk_tokens(text: &str, chunk_tokens: usize) -> Self {
+        let mut chunks = Vec::new();
+        let mut hasher = TokenHasher::new(chunk_tokens);
+        hasher.update(text.as_bytes(), |chunk| chunks.push(chunk));
+        let token_count = hasher.finish(|chunk| chunks.push(chunk));
+        ChunkedDigest {
+            chunk_tokens,
+            token_count,
+            chunks,
+        }
+    }
+}
+
+/// Checks output against a [`ChunkedDigest`] as it is written, remembering the first
+/// chunk that differs.
+pub struct StreamingVerifier<'a> {
+    expected: &'a ChunkedDigest,
+    hasher: TokenHasher,
+    /// Index of the next chunk to compare.
+    chunk: usize,
+    first_mismatch: Option<usize>,
+}
+
+impl<'a> StreamingVerifier<'a> {
+    pub fn new(expected: &'a ChunkedDigest) -> Self {
+        StreamingVerifier {
+            expected,
+            hasher: TokenHasher::new(expected.chunk_tokens),
+            chunk: 0,
+            first_mismatch: None,
+        }
+    }
+
+    /// Ends the output; returns where it first differs from the expected output, if
+    /// anywhere.
+    pub fn finish(self) -> Option<ChunkMismatch> {
+        let StreamingVerifier {
+            expected,
+            hasher,
+            mut chunk,
+            mut first_mismatch,
+        } = self;
+        let token_count =
+            hasher.finish(|digest| compare(expected, &mut chunk, &mut first_mismatch, digest));
+        // Chunks that are missing altogether never got compared.
+        if first_mismatch.is_none() && chunk < expected.chunks.len() {
+            first_mismatch = Some(chunk);
+        }
+        first_mismatch.map(|chunk| ChunkMismatch {
+            chunk,
+            chunk_tokens: expected.chunk_tokens,
+            expected_tokens: expected.token_count,
+            actual_tokens: token_count,
+        })
+    }
+}
+
+impl Write for StreamingVerifier<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let Self {
+            expected,
+            hasher,
+            chunk,
+            first_mismatch,
+        } = self;
+        hasher.update(buf, |digest| {
+            compare(expected, chunk, first_mismatch, digest)
+        });
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Compares the digest of chunk `chunk` with the expected one and moves on to the next.
+fn compare(
+    expected: &ChunkedDigest,
+    chunk: &mut usize,
+    first_mismatch: &mut Option<usize>,
+    digest: String,
+) {
+    if first_mismatch.is_none() && expected.chunks.get(*chunk) != Some(&digest) {
+        *first_mismatch = Some(*chunk);
+    }
+    *chunk += 1;
+}
+
+/// The first chunk in which an output differs from the expected output.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChunkMismatch {
+    /// Zero-based index of the chunk.
+    pub chunk: usize,
+    pub chunk_tokens: usize,
+    pub expected_tokens: u64,
+    pub actual_tokens: u64,
+}
+
+impl fmt::Display for ChunkMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let first = self.chunk as u64 * self.chunk_tokens as u64 + 1;
+        let last = (first + self.chunk_tokens as u64 - 1)
+            .min(self.expected_tokens.max(self.actual_tokens))
+            .max(first);
+        write!(
+            f,
+            "Output first differs in chunk {} (tokens {}..={}); expected {} tokens, got {}.",
+            self.chunk + 1,
+            first,
+            last,
+            self.expected_tokens,
+            self.actual_tokens
+        )
+    }
+}
+
+/// Splits a byte stream into tokens separated by ASCII whitespace and hashes them in
+/// chunks, each token followed by a single space.
+struct TokenHasher {
+    chunk_tokens: usize,
+    hasher: Sha256,
+    /// Whether the last byte seen was part of a token.
+    in_token: bool,
+    tokens_in_chunk: usize,
+    token_count: u64,
+}
+
+impl TokenHasher {
+    fn new(chunk_tokens: usize) -> Self {
+        TokenHasher {
+            chunk_tokens,
+            hasher: Sha256::new(),
+            in_token: false,
+            tokens_in_chunk: 0,
+            token_count: 0,
+        }
+    }
+
+    /// Hashes `bytes`, passing the digest of every completed chunk to `on_chunk`.
+    fn update(&mut self, bytes: &[u8], mut on_chunk: impl FnMut(String)) {
+        // Start of the token bytes in `bytes` that are not hashed yet.
+        let mut start = None;
+        for (i, byte) in bytes.iter().enumerate() {
+            if byte.is_ascii_whitespace() {
+                if let Some(start) = start.take() {
+                    self.hasher.update(&bytes[start..i]);
+                }
+                if self.in_token {
+                    self.end_token(&mut on_chunk);
+                }
+            } else {
+                start.get_or_insert(i);
+                self.in_token = true;
+            }
+        }
+        if let Some(start) = start {
+            self.hasher.update(&bytes[start..]);
+        }
+    }
+
+    /// Ends the stream, passing the digest of the last chunk to `on_chunk`, and
+    /// returns the number of tokens seen.
+    fn finish(mut self, mut on_chunk: impl FnMut(String)) -> u64 {
+        if self.in_token {
+            self.end_token(&mut on_chunk);
+        }
+        if self.tokens_in_chunk > 0 {
+            on_chunk(self.finish_chunk());
+        }
+        self.token_count
+    }
+
+    fn end_token(&mut self, on_chunk: &mut impl FnMut(String)) {
+        self.in_token = false;
+        self.hasher.update(b" ");
+        self.token_count += 1;
+        self.tokens_in_chunk += 1;
+        if self.tokens_in_chunk == self.chunk_tokens {
+            on_chunk(self.finish_chunk());
+        }
+    }
+
+    fn finish_chunk(&mut self) -> String {
+        self.tokens_in_chunk = 0;
+        format!("{:x}", self.hasher.finalize_reset())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Checker;
+
+    const EXPECTED: &str = "alpha beta gamma delta epsilon zeta eta";
+
+    /// Streams `pieces` into a verifier for `expected`, two tokens per chunk.
+    fn verify(expected: &str, pieces: &[&[u8]]) -> Option<ChunkMismatch> {
+        let digest = ChunkedDigest::with_chunk_tokens(expected, 2);
+        let mut verifier = StreamingVerifier::new(&digest);
+        for piece in pieces {
+            verifier.write_all(piece).unwrap();
+        }
+        verifier.finish()
+    }
+
+    #[test]
+    fn any_split_of_the_same_output_matches() {
+        let output = "alpha  beta\tgamma\r\ndelta\n epsilon zeta eta\n".as_bytes();
+        assert_eq!(verify(EXPECTED, &[output]), None);
+        for i in 0..=output.len() {
+            assert_eq!(verify(EXPECTED, &[&output[..i], &output[i..]]), None);
+        }
+        let bytes: Vec<&[u8]> = output.chunks(1).collect();
+        assert_eq!(verify(EXPECTED, &bytes), None);
+    }
+
+    #[test]
+    fn changed_token_reports_its_chunk() {
+        let mismatch = verify(EXPECTED, &[b"alpha beta gamma delta EPSILON zeta eta"]).unwrap();
+        assert_eq!(mismatch.chunk, 2);
+        assert_eq!(mismatch.expected_tokens, 7);
+        assert_eq!(mismatch.actual_tokens, 7);
+        // A token split across writes is still one token.
+        let mismatch = verify(EXPECTED, &[b"alpha be", b"ta gamma delta epsilon zeta eta"]);
+        assert_eq!(mismatch, None);
+        let mismatch = verify(
+            EXPECTED,
+            &[b"alpha be", b" ta gamma delta epsilon zeta eta"],
+        );
+        assert_eq!(mismatch.unwrap().chunk, 0);
+    }
+
+    #[test]
+    fn extra_or_missing_tokens_report_the_last_chunk() {
+        let mismatch = verify(EXPECTED, &[EXPECTED.as_bytes(), b" theta"]).unwrap();
+        assert_eq!(mismatch.chunk, 3);
+        assert_eq!(mismatch.actual_tokens, 8);
+        // With a full last chunk, an extra token starts a chunk that is not expected.
+        let mismatch = verify("a b c d", &[b"a b c d e"]).unwrap();
+        assert_eq!(mismatch.chunk, 2);
+        let mismatch = verify(EXPECTED, &[b"alpha beta gamma delta"]).unwrap();
+        assert_eq!(mismatch.chunk, 2);
+        assert_eq!(mismatch.actual_tokens, 4);
+    }
+
+    #[test]
+    fn splits_tokens_like_the_tokens_checker() {
+        for output in [
+            "alpha beta gamma delta epsilon zeta eta",
+            "alpha\u{a0}beta gamma delta epsilon zeta eta",
+            "alpha\u{2003}beta gamma delta epsilon zeta eta",
+            "alpha\x0cbeta gamma delta epsilon zeta eta",
+            "alpha\x0bbeta gamma delta epsilon zeta eta",
+        ] {
+            assert_eq!(
+                Checker::Tokens.check("", EXPECTED, output),
+                verify(EXPECTED, &[output.as_bytes()]).is_none(),
+                "{output:?}"
+            );
+        }
+    }
+}
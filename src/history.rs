@@ -0,0 +1,324 @@
+//! Results history across sessions.
+//!
+//! Every session appends one [`HistoryRecord`] per executable to [`HISTORY_FILE`], an
+//! append-only JSON Lines file. Records are keyed by the executable's SHA-256, the
+//! challenge, its input parameters and a fingerprint of the machine, so timings are
+//! only ever compared with timings of the same work on the same hardware.
+
+use crate::input_cache::sha256_hex;
+use crate::report::SessionReport;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+
+/// Where the history of every session is appended.
+pub const HISTORY_FILE: &str = "./results/history.jsonl";
+
+/// One executable's measured runs in one session.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryRecord {
+    /// When the session started, in seconds since the Unix epoch.
+    pub recorded_at: u64,
+    /// The session directory, for looking up the full report.
+    pub session: String,
+    pub challenge: String,
+    pub params: BTreeMap<String, u128>,
+    pub seed: u64,
+    /// See [`Machine::fingerprint`].
+    pub machine: String,
+    /// File name of the executable; versions of a submission share it.
+    pub executable: String,
+    /// SHA-256 of the executable, which tells its versions apart.
+    pub sha256: String,
+    pub all_accepted: bool,
+    /// Wall-clock times of the accepted measured runs, in seconds. Runs that failed
+    /// are left out, as a crash is usually quicker than a correct answer.
+    pub times: Vec<f64>,
+}
+
+/// The machine a session ran on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Machine {
+    /// Host name, operating system, architecture, CPU model and core count.
+    pub description: String,
+    /// Short hash of the description.
+    pub fingerprint: String,
+}
+
+impl Machine {
+    /// Describes the machine this process runs on.
+    pub fn current() -> Self {
+        let host = fs::read_to_string("/etc/hostname")
+            .ok()
+            .or_else(|| std::env::var("COMPUTERNAME").ok())
+            .or_else(|| std::env::var("HOSTNAME").ok())
+            .map(|host| host.trim().to_string())
+            .unwrap_or_else(|| String::from("unknown host"));
+        let cpu = fs::read_to_string("/proc/cpuinfo")
+            .ok()
+            .and_then(|cpuinfo| {
+                cpuinfo
+                    .lines()
+                    .find(|line| line.starts_with("model name"))
+                    .and_then(|line| line.split(':').nth(1))
+                    .map(|model| model.trim().to_string())
+            })
+            .unwrap_or_else(|| String::from("unknown CPU"));
+        let cores = std::thread::available_parallelism().map_or(1, |cores| cores.get());
+        let description = format!(
+            "{host}, {} {}, {cpu}, {cores} cores",
+            std::env::consts::OS,
+            std::env::consts::ARCH
+        );
+        Machine {
+            fingerprint: sha256_hex(description.as_bytes())[..16].to_string(),
+            description,
+        }
+    }
+}
+
+/// Appends a record for every executable in `report` that has accepted runs.
+pub fn record_session(
+    report: &SessionReport,
+    session_dir: &Path,
+    machine: &Machine,
+) -> Result<(), Box<dyn Error>> {
+    let path = Path::new(HISTORY_FILE);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    for record in records_for(report, session_dir, machine) {
+        writeln!(file, "{}", serde_json::to_string(&record)?)?;
+    }
+    Ok(())
+}
+
+/// The history records of the executables in `report` that have accepted runs and
+/// could be hashed.
+pub fn records_for(
+    report: &SessionReport,
+    session_dir: &Path,
+    machine: &Machine,
+) -> Vec<HistoryRecord> {
+    report
+        .executables
+        .iter()
+        .filter_map(|executable| {
+            let (Some(sha256), Some(_)) = (&executable.sha256, &executable.wall_time_secs) else {
+                return None;
+            };
+            Some(HistoryRecord {
+                recorded_at: report.started_at,
+                session: session_dir.display().to_string(),
+                challenge: report.challenge.clone(),
+                params: report.params.clone(),
+                seed: report.seed,
+                machine: machine.fingerprint.clone(),
+                executable: executable.name.clone(),
+                sha256: sha256.clone(),
+                all_accepted: executable.all_accepted,
+                times: executable
+                    .runs
+                    .iter()
+                    .filter(|run| !run.warmup && run.accepted)
+                    .map(|run| run.wall_time_secs)
+                    .collect(),
+            })
+        })
+        .collect()
+}
+
+/// Every record in the history, oldest first. Lines that do not parse, e.g. from a
+/// session that was interrupted mid-write, are skipped.
+pub fn load_history() -> Result<Vec<HistoryRecord>, Box<dyn Error>> {
+    let text = match fs::read_to_string(HISTORY_FILE) {
+        Ok(text) => text,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(format!("failed to read {HISTORY_FILE}: {err}").into()),
+    };
+    Ok(text
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+/// The recorded runs of one version of an executable, pooled over its sessions.
+#[derive(Debug, Clone)]
+pub struct Version {
+    pub sha256: String,
+    /// When the version was first and last benchmarked.
+    pub first_seen: u64,
+    pub last_seen: u64,
+    pub sessions: usize,
+    pub all_accepted: bool,
+    pub times: Vec<f64>,
+}
+
+/// The versions of `executable` found in `records`, most recently benchmarked first.
+pub fn versions(records: &[HistoryRecord], executable: &str) -> Vec<Version> {
+    let mut versions: Vec<Version> = Vec::new();
+    // Newest records first, so versions benchmarked in the same second keep their order.
+    for record in records
+        .iter()
+        .rev()
+        .filter(|record| record.executable == executable)
+    {
+        match versions
+            .iter_mut()
+            .find(|version| version.sha256 == record.sha256)
+        {
+            Some(version) => {
+                version.first_seen = version.first_seen.min(record.recorded_at);
+                version.last_seen = version.last_seen.max(record.recorded_at);
+                version.sessions += 1;
+                version.all_accepted &= record.all_accepted;
+                version.times.extend(&record.times);
+            }
+            None => versions.push(Version {
+                sha256: record.sha256.clone(),
+                first_seen: record.recorded_at,
+                last_seen: record.recorded_at,
+                sessions: 1,
+                all_accepted: record.all_accepted,
+                times: record.times.clone(),
+            }),
+        }
+    }
+    versions.sort_by_key(|version| Reverse(version.last_seen));
+    versions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::{ExecutableReport, RunRecord};
+    use crate::stats::summarize;
+
+    /// A run with the short verdict `verdict`, taking `time` seconds.
+    fn run(run: u32, warmup: bool, verdict: &str, time: f64) -> RunRecord {
+        RunRecord {
+            run,
+            warmup,
+            seed: 7,
+            verdict: verdict.to_string(),
+            verdict_detail: verdict.to_string(),
+            accepted: verdict == "AC",
+            wall_time_secs: time,
+            peak_rss_bytes: None,
+            user_cpu_secs: None,
+            system_cpu_secs: None,
+            voluntary_context_switches: None,
+            involuntary_context_switches: None,
+        }
+    }
+
+    fn executable(name: &str, sha256: Option<&str>, runs: Vec<RunRecord>) -> ExecutableReport {
+        let times: Vec<f64> = runs
+            .iter()
+            .filter(|run| !run.warmup && run.accepted)
+            .map(|run| run.wall_time_secs)
+            .collect();
+        ExecutableReport {
+            name: name.to_string(),
+            path: format!("./{name}"),
+            sha256: sha256.map(str::to_string),
+            all_accepted: runs.iter().all(|run| run.accepted),
+            runs,
+            wall_time_secs: summarize(&times),
+            threading: None,
+        }
+    }
+
+    fn session(started_at: u64, executables: Vec<ExecutableReport>) -> SessionReport {
+        SessionReport {
+            challenge: String::from("gcd"),
+            challenge_version: 1,
+            params: BTreeMap::from([(String::from("pairs"), 100)]),
+            seed: 7,
+            fresh_inputs: None,
+            warmup_runs: 1,
+            time_limit_secs: 1.0,
+            memory_limit_bytes: None,
+            started_at,
+            reference_time_secs: None,
+            executables,
+        }
+    }
+
+    fn machine() -> Machine {
+        Machine {
+            description: String::from("test machine"),
+            fingerprint: String::from("0123456789abcdef"),
+        }
+    }
+
+    #[test]
+    fn records_keep_only_accepted_measured_times() {
+        let report = session(
+            100,
+            vec![executable(
+                "flaky",
+                Some("aaaa"),
+                vec![
+                    run(1, true, "AC", 5.0),
+                    run(1, false, "AC", 0.3),
+                    run(2, false, "RE", 0.01),
+                    run(3, false, "TLE", 1.0),
+                    run(4, false, "AC", 0.2),
+                ],
+            )],
+        );
+        let records = records_for(&report, Path::new("results/gcd-100"), &machine());
+        assert_eq!(records.len(), 1);
+        let record = &records[0];
+        assert_eq!(record.times, [0.3, 0.2]);
+        assert!(!record.all_accepted);
+        assert_eq!(record.session, "results/gcd-100");
+        assert_eq!(record.machine, "0123456789abcdef");
+        assert_eq!(record.sha256, "aaaa");
+    }
+
+    #[test]
+    fn records_skip_executables_without_accepted_runs_or_hash() {
+        let report = session(
+            100,
+            vec![
+                executable("crashed", Some("aaaa"), vec![run(1, false, "RE", 0.01)]),
+                executable("unreadable", None, vec![run(1, false, "AC", 0.1)]),
+            ],
+        );
+        assert!(records_for(&report, Path::new("results/gcd-100"), &machine()).is_empty());
+    }
+
+    #[test]
+    fn versions_pool_sessions_newest_first() {
+        let mut records = Vec::new();
+        for (started_at, sha256, runs) in [
+            (
+                100,
+                "old",
+                vec![run(1, false, "AC", 0.5), run(2, false, "RE", 0.01)],
+            ),
+            (200, "new", vec![run(1, false, "AC", 0.25)]),
+            (300, "old", vec![run(1, false, "AC", 0.75)]),
+        ] {
+            let report = session(started_at, vec![executable("sub", Some(sha256), runs)]);
+            records.extend(records_for(&report, Path::new("results"), &machine()));
+        }
+        let found = versions(&records, "sub");
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].sha256, "old");
+        assert_eq!((found[0].first_seen, found[0].last_seen), (100, 300));
+        assert_eq!(found[0].sessions, 2);
+        assert!(!found[0].all_accepted);
+        assert_eq!(found[0].times, [0.75, 0.5]);
+        assert_eq!(found[1].sha256, "new");
+        assert_eq!(found[1].times, [0.25]);
+        assert!(versions(&records, "other").is_empty());
+    }
+}
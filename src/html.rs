@@ -0,0 +1,545 @@
+//! Self-contained HTML report of a session.
+//!
+//! The page is rendered from the same [`SessionReport`] as `report.json`, plus any
+//! `scale` curves of its executables. Charts are inline SVG and styles are inline CSS,
+//! so the file needs no network access and can be attached or pasted anywhere.
+
+use crate::challenges::format_param_value;
+use crate::complexity::{ClassFit, PowerFit};
+use crate::report::{ExecutableReport, RunRecord, ScaleReport, SessionReport};
+use crate::{format_bytes, format_time};
+use std::fmt::Write;
+
+/// Name of the HTML report in a session directory.
+pub const HTML_FILE: &str = "report.html";
+
+/// Line and dot colours, one per executable in leaderboard order.
+const PALETTE: [&str; 8] = [
+    "#1f77b4", "#ff7f0e", "#2ca02c", "#d62728", "#9467bd", "#8c564b", "#e377c2", "#17becf",
+];
+
+const ACCEPTED_COLOR: &str = "#2ca02c";
+const REJECTED_COLOR: &str = "#d62728";
+
+/// Width of every chart, and of the label column left of the plot area.
+const CHART_WIDTH: f64 = 860.0;
+const LABEL_WIDTH: f64 = 180.0;
+
+const STYLE: &str = "
+body { font-family: system-ui, sans-serif; margin: 2em auto; max-width: 960px; color: #222; }
+h1, h2, h3 { font-weight: 600; }
+table { border-collapse: collapse; margin: 0.5em 0 1.5em; font-size: 0.9em; }
+th, td { border-bottom: 1px solid #ddd; padding: 0.3em 0.8em; text-align: left; }
+th { background: #f4f4f4; }
+td.number { text-align: right; font-variant-numeric: tabular-nums; }
+.accepted { color: #2ca02c; }
+.rejected { color: #d62728; }
+.muted { color: #777; }
+svg text { font-size: 12px; fill: #333; }
+details { margin-bottom: 1em; }
+summary { cursor: pointer; font-weight: 600; }
+";
+
+/// Renders `report` as a standalone HTML page, with the scaling curves in `scales`.
+pub fn render(report: &SessionReport, scales: &[ScaleReport]) -> String {
+    let mut html = String::new();
+    let title = format!(
+        "{} benchmark, {}",
+        report.challenge,
+        format_utc(report.started_at)
+    );
+    let _ = write!(
+        html,
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>{}</title>\n<style>{STYLE}</style>\n</head>\n<body>\n<h1>{}</h1>\n",
+        escape(&title),
+        escape(&title)
+    );
+    session_section(&mut html, report);
+    leaderboard_section(&mut html, report);
+    distribution_section(&mut html, report);
+    if !scales.is_empty() {
+        scaling_section(&mut html, scales);
+    }
+    details_section(&mut html, report);
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+/// The settings needed to reproduce the session.
+fn session_section(html: &mut String, report: &SessionReport) {
+    let params: Vec<String> = report
+        .params
+        .iter()
+        .map(|(name, value)| format!("{name} = {value}"))
+        .collect();
+    let mut rows = vec![
+        (
+            "Challenge",
+            format!(
+                "{} (version {})",
+                report.challenge, report.challenge_version
+            ),
+        ),
+        ("Parameters", params.join(", ")),
+        ("Seed", report.seed.to_string()),
+        ("Time limit", format_time(report.time_limit_secs)),
+        (
+            "Memory limit",
+            report
+                .memory_limit_bytes
+                .map_or_else(|| String::from("none"), format_bytes),
+        ),
+        ("Warmup runs", report.warmup_runs.to_string()),
+    ];
+    if let Some(fresh) = &report.fresh_inputs {
+        rows.push(("Fresh inputs", format!("one seed per {fresh}")));
+    }
+    if let Some(reference) = report.reference_time_secs {
+        rows.push(("Reference (in-process)", format_time(reference)));
+    }
+    html.push_str("<h2>Session</h2>\n<table>\n");
+    for (name, value) in rows {
+        let _ = writeln!(html, "<tr><th>{name}</th><td>{}</td></tr>", escape(&value));
+    }
+    html.push_str("</table>\n");
+}
+
+fn leaderboard_section(html: &mut String, report: &SessionReport) {
+    html.push_str(
+        "<h2>Leaderboard</h2>\n<table>\n<tr><th>Rank</th><th>Executable</th><th>Median</th>\
+         <th>vs fastest</th><th>vs reference</th><th>Verdicts</th></tr>\n",
+    );
+    let ratio =
+        |ratio: Option<f64>| ratio.map_or_else(|| String::from("-"), |r| format!("{r:.2}x"));
+    let mut category = None;
+    for standing in report.leaderboard() {
+        // Single- and multi-threaded executables are ranked separately.
+        if category != Some(standing.category()) {
+            category = Some(standing.category());
+            let _ = writeln!(
+                html,
+                "<tr><th colspan=\"6\">{}</th></tr>",
+                standing.category()
+            );
+        }
+        let class = if standing.rank.is_some() {
+            "accepted"
+        } else {
+            "rejected"
+        };
+        let _ = writeln!(
+            html,
+            "<tr><td class=\"number\">{}</td><td>{}</td><td class=\"number\">{}</td>\
+             <td class=\"number\">{}</td><td class=\"number\">{}</td><td class=\"{class}\">{}</td></tr>",
+            standing
+                .rank
+                .map_or_else(|| String::from("-"), |rank| rank.to_string()),
+            escape(&standing.name),
+            standing
+                .median
+                .map_or_else(|| String::from("-"), format_time),
+            ratio(standing.vs_fastest),
+            ratio(standing.vs_reference),
+            escape(&standing.verdicts)
+        );
+    }
+    html.push_str("</table>\n");
+}
+
+/// One row per executable: every measured run as a dot on a shared time axis, over a
+/// box from the first to the third quartile with a line at the median.
+fn distribution_section(html: &mut String, report: &SessionReport) {
+    let executables = ranked_executables(report);
+    let times: Vec<f64> = executables
+        .iter()
+        .flat_map(|executable| measured_runs(executable))
+        .map(|run| run.wall_time_secs)
+        .collect();
+    let min_time = times.iter().copied().fold(f64::INFINITY, f64::min);
+    let max_time = times.iter().copied().fold(0.0, f64::max);
+    if times.is_empty() || max_time <= 0.0 {
+        return;
+    }
+    // Zoom in on the measured range, so close submissions can be told apart.
+    let margin = ((max_time - min_time) * 0.1).max(max_time * 0.01);
+    let axis_min = (min_time - margin).max(0.0);
+    let axis_max = max_time + margin;
+    let row_height = 36.0;
+    let top = 10.0;
+    let plot_width = CHART_WIDTH - LABEL_WIDTH - 20.0;
+    let height = top + row_height * executables.len() as f64 + 30.0;
+    let x = |secs: f64| LABEL_WIDTH + (secs - axis_min) / (axis_max - axis_min) * plot_width;
+
+    html.push_str("<h2>Timing distributions</h2>\n");
+    html.push_str(
+        "<p class=\"muted\">Wall-clock time of every measured run; the axis does not start \
+         at zero. The box spans the first to third quartile; the dark line marks the median. \
+         Red dots are rejected runs.</p>\n",
+    );
+    let _ = writeln!(
+        html,
+        "<svg width=\"{CHART_WIDTH}\" height=\"{height}\" viewBox=\"0 0 {CHART_WIDTH} {height}\" \
+         xmlns=\"http://www.w3.org/2000/svg\">"
+    );
+    for (row, executable) in executables.iter().enumerate() {
+        let center = top + row_height * (row as f64 + 0.5);
+        let _ = writeln!(
+            html,
+            "<text x=\"{}\" y=\"{}\" text-anchor=\"end\">{}</text>",
+            LABEL_WIDTH - 10.0,
+            center + 4.0,
+            escape(&executable.name)
+        );
+        if let Some(stats) = &executable.wall_time_secs {
+            let _ = writeln!(
+                html,
+                "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" fill=\"#e8eef7\" \
+                 stroke=\"#9ab\"/>\n<line x1=\"{m:.1}\" x2=\"{m:.1}\" y1=\"{:.1}\" y2=\"{:.1}\" \
+                 stroke=\"#123\" stroke-width=\"2\"/>",
+                x(stats.q1),
+                center - 10.0,
+                (x(stats.q3) - x(stats.q1)).max(1.0),
+                20.0,
+                center - 12.0,
+                center + 12.0,
+                m = x(stats.median)
+            );
+        }
+        for run in measured_runs(executable) {
+            let color = if run.accepted {
+                ACCEPTED_COLOR
+            } else {
+                REJECTED_COLOR
+            };
+            let _ = writeln!(
+                html,
+                "<circle cx=\"{:.1}\" cy=\"{center:.1}\" r=\"3\" fill=\"{color}\" \
+                 fill-opacity=\"0.6\"><title>Run {}: {}</title></circle>",
+                x(run.wall_time_secs),
+                run.run,
+                format_time(run.wall_time_secs)
+            );
+        }
+    }
+    let axis_y = top + row_height * executables.len() as f64 + 5.0;
+    let ticks: Vec<(f64, String)> = (0..=5)
+        .map(|i| {
+            let secs = axis_min + (axis_max - axis_min) * i as f64 / 5.0;
+            (x(secs), format_time(secs))
+        })
+        .collect();
+    axis(html, axis_y, &ticks);
+    html.push_str("</svg>\n");
+}
+
+/// One chart per series of `scale` curves, as only curves over the same sizes with
+/// the same other parameters are comparable.
+fn scaling_section(html: &mut String, scales: &[ScaleReport]) {
+    html.push_str("<h2>Scaling</h2>\n");
+    html.push_str(
+        "<p class=\"muted\">Median time against input size on log-log axes, from the latest \
+         <code>scale</code> run of each executable. A straight line of slope k means time \
+         grows like n<sup>k</sup>.</p>\n",
+    );
+    let mut series: Vec<Vec<&ScaleReport>> = Vec::new();
+    for scale in scales {
+        match series
+            .iter_mut()
+            .find(|curves| curves[0].same_series(scale))
+        {
+            Some(curves) => curves.push(scale),
+            None => series.push(vec![scale]),
+        }
+    }
+    for curves in &series {
+        scaling_chart(html, curves);
+    }
+}
+
+/// Time against input size on log-log axes, one line per executable.
+fn scaling_chart(html: &mut String, scales: &[&ScaleReport]) {
+    let points = scales
+        .iter()
+        .flat_map(|scale| &scale.points)
+        .filter(|point| point.size > 0 && point.median_time_secs > 0.0);
+    let (mut min_size, mut max_size) = (f64::INFINITY, 0.0f64);
+    let (mut min_time, mut max_time) = (f64::INFINITY, 0.0f64);
+    for point in points {
+        min_size = min_size.min(point.size as f64);
+        max_size = max_size.max(point.size as f64);
+        min_time = min_time.min(point.median_time_secs);
+        max_time = max_time.max(point.median_time_secs);
+    }
+    if max_size <= 0.0 {
+        return;
+    }
+    // Pad degenerate ranges so a single size or time still gets an axis.
+    if max_size <= min_size {
+        max_size = min_size * 2.0;
+    }
+    if max_time <= min_time {
+        max_time = min_time * 2.0;
+    }
+    let (left, right, top, bottom) = (70.0, 20.0, 10.0, 40.0);
+    let height = 360.0;
+    let plot_width = CHART_WIDTH - left - right;
+    let plot_height = height - top - bottom;
+    let log_position =
+        |value: f64, min: f64, max: f64| (value.ln() - min.ln()) / (max.ln() - min.ln());
+    let x = |size: f64| left + log_position(size, min_size, max_size) * plot_width;
+    let y = |secs: f64| top + (1.0 - log_position(secs, min_time, max_time)) * plot_height;
+
+    let _ = writeln!(html, "<h3>{}</h3>", escape(&scales[0].series_label()));
+    let _ = writeln!(
+        html,
+        "<svg width=\"{CHART_WIDTH}\" height=\"{height}\" viewBox=\"0 0 {CHART_WIDTH} {height}\" \
+         xmlns=\"http://www.w3.org/2000/svg\">"
+    );
+    let _ = writeln!(
+        html,
+        "<rect x=\"{left}\" y=\"{top}\" width=\"{plot_width}\" height=\"{plot_height}\" \
+         fill=\"none\" stroke=\"#ccc\"/>"
+    );
+    for i in 0..=4 {
+        let fraction = i as f64 / 4.0;
+        let secs = (min_time.ln() + fraction * (max_time.ln() - min_time.ln())).exp();
+        let _ = writeln!(
+            html,
+            "<text x=\"{}\" y=\"{:.1}\" text-anchor=\"end\">{}</text>",
+            left - 6.0,
+            y(secs) + 4.0,
+            format_time(secs)
+        );
+    }
+    let ticks: Vec<(f64, String)> = (0..=4)
+        .map(|i| {
+            let fraction = i as f64 / 4.0;
+            let size = (min_size.ln() + fraction * (max_size.ln() - min_size.ln())).exp();
+            (x(size), format_param_value(size.round() as u128))
+        })
+        .collect();
+    axis(html, top + plot_height, &ticks);
+    for (i, scale) in scales.iter().enumerate() {
+        let color = PALETTE[i % PALETTE.len()];
+        let coordinates: Vec<String> = scale
+            .points
+            .iter()
+            .filter(|point| point.size > 0 && point.median_time_secs > 0.0)
+            .map(|point| {
+                format!(
+                    "{:.1},{:.1}",
+                    x(point.size as f64),
+                    y(point.median_time_secs)
+                )
+            })
+            .collect();
+        let _ = writeln!(
+            html,
+            "<polyline points=\"{}\" fill=\"none\" stroke=\"{color}\" stroke-width=\"2\"/>",
+            coordinates.join(" ")
+        );
+        for coordinate in &coordinates {
+            let (cx, cy) = coordinate.split_once(',').unwrap_or_default();
+            let _ = writeln!(
+                html,
+                "<circle cx=\"{cx}\" cy=\"{cy}\" r=\"3\" fill=\"{color}\"/>"
+            );
+        }
+        let _ = writeln!(
+            html,
+            "<text x=\"{}\" y=\"{}\" fill=\"{color}\">{}</text>",
+            left + 10.0,
+            top + 18.0 + 16.0 * i as f64,
+            escape(scale.executable_name())
+        );
+    }
+    html.push_str("</svg>\n");
+
+    html.push_str(
+        "<table>\n<tr><th>Executable</th><th>Time: best fit</th><th>Time: exponent</th>\
+         <th>Memory: best fit</th><th>Memory: exponent</th></tr>\n",
+    );
+    for scale in scales {
+        let class = |fit: Option<ClassFit>| {
+            fit.map_or_else(
+                || String::from("-"),
+                |fit| {
+                    format!(
+                        "{} ({:.1}% error)",
+                        fit.complexity,
+                        fit.relative_error * 100.0
+                    )
+                },
+            )
+        };
+        let power = |fit: Option<PowerFit>| {
+            fit.map_or_else(
+                || String::from("-"),
+                |fit| format!("n^{:.2} (R² {:.3})", fit.exponent, fit.r_squared),
+            )
+        };
+        let _ = writeln!(
+            html,
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            escape(scale.executable_name()),
+            class(scale.time_class_fit),
+            power(scale.time_power_fit),
+            class(scale.memory_class_fit),
+            power(scale.memory_power_fit)
+        );
+    }
+    html.push_str("</table>\n");
+}
+
+/// Statistics and every run of each executable, collapsed by default.
+fn details_section(html: &mut String, report: &SessionReport) {
+    html.push_str("<h2>Runs</h2>\n");
+    for executable in ranked_executables(report) {
+        let class = if executable.all_accepted {
+            "accepted"
+        } else {
+            "rejected"
+        };
+        let _ = writeln!(
+            html,
+            "<details>\n<summary>{} <span class=\"{class}\">({})</span></summary>",
+            escape(&executable.name),
+            escape(&executable.verdict_counts())
+        );
+        let _ = writeln!(
+            html,
+            "<p class=\"muted\">{}<br>SHA-256 {}</p>",
+            escape(&executable.path),
+            executable.sha256.as_deref().unwrap_or("unknown")
+        );
+        if let Some(stats) = &executable.wall_time_secs {
+            let _ = writeln!(
+                html,
+                "<p>Mean {} ± {} · median {} (95% CI {} – {}) · min {} · max {} · p90 {} · \
+                 p99 {} · MAD {} · {} outliers</p>",
+                format_time(stats.mean),
+                format_time(stats.std_dev),
+                format_time(stats.median),
+                format_time(stats.median_ci.low),
+                format_time(stats.median_ci.high),
+                format_time(stats.min),
+                format_time(stats.max),
+                format_time(stats.p90),
+                format_time(stats.p99),
+                format_time(stats.mad),
+                stats.outliers.len()
+            );
+        }
+        html.push_str(
+            "<table>\n<tr><th>Run</th><th>Seed</th><th>Verdict</th><th>Time</th>\
+             <th>Peak RSS</th><th>User CPU</th><th>Sys CPU</th><th>Ctx switches</th></tr>\n",
+        );
+        for run in &executable.runs {
+            let label = if run.warmup {
+                format!("warmup {}", run.run)
+            } else {
+                run.run.to_string()
+            };
+            let class = if run.accepted { "accepted" } else { "rejected" };
+            let optional_time =
+                |secs: Option<f64>| secs.map_or_else(|| String::from("-"), format_time);
+            let switches = match (
+                run.voluntary_context_switches,
+                run.involuntary_context_switches,
+            ) {
+                (Some(voluntary), Some(involuntary)) => format!("{voluntary}/{involuntary}"),
+                _ => String::from("-"),
+            };
+            let _ = writeln!(
+                html,
+                "<tr><td>{label}</td><td class=\"number\">{}</td><td class=\"{class}\">{}</td>\
+                 <td class=\"number\">{}</td><td class=\"number\">{}</td><td class=\"number\">{}</td>\
+                 <td class=\"number\">{}</td><td class=\"number\">{switches}</td></tr>",
+                run.seed,
+                escape(&run.verdict_detail),
+                format_time(run.wall_time_secs),
+                run.peak_rss_bytes
+                    .map_or_else(|| String::from("-"), format_bytes),
+                optional_time(run.user_cpu_secs),
+                optional_time(run.system_cpu_secs)
+            );
+        }
+        html.push_str("</table>\n</details>\n");
+    }
+}
+
+/// Draws a horizontal axis at height `y` with labelled `ticks` at x positions.
+fn axis(html: &mut String, y: f64, ticks: &[(f64, String)]) {
+    let (Some(first), Some(last)) = (ticks.first(), ticks.last()) else {
+        return;
+    };
+    let _ = writeln!(
+        html,
+        "<line x1=\"{:.1}\" x2=\"{:.1}\" y1=\"{y:.1}\" y2=\"{y:.1}\" stroke=\"#999\"/>",
+        first.0, last.0
+    );
+    for (x, label) in ticks {
+        let _ = writeln!(
+            html,
+            "<line x1=\"{x:.1}\" x2=\"{x:.1}\" y1=\"{y:.1}\" y2=\"{:.1}\" stroke=\"#999\"/>\n\
+             <text x=\"{x:.1}\" y=\"{:.1}\" text-anchor=\"middle\">{}</text>",
+            y + 5.0,
+            y + 18.0,
+            escape(label)
+        );
+    }
+}
+
+/// The executables in leaderboard order.
+fn ranked_executables(report: &SessionReport) -> Vec<&ExecutableReport> {
+    report
+        .leaderboard()
+        .iter()
+        .filter_map(|standing| {
+            report
+                .executables
+                .iter()
+                .find(|executable| executable.name == standing.name)
+        })
+        .collect()
+}
+
+fn measured_runs(executable: &ExecutableReport) -> impl Iterator<Item = &RunRecord> {
+    executable.runs.iter().filter(|run| !run.warmup)
+}
+
+/// Escapes text for use in HTML content and attribute values.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Formats seconds since the Unix epoch as a UTC date and time, e.g.
+/// `2025-03-14 15:09 UTC`.
+fn format_utc(secs: u64) -> String {
+    let days = (secs / 86_400) as i64;
+    let minutes_of_day = secs % 86_400 / 60;
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm).
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02} UTC",
+        minutes_of_day / 60,
+        minutes_of_day % 60
+    )
+}
@@ -0,0 +1,430 @@
+//! On-disk cache of generated inputs and their reference outputs.
+//!
+//! Each input in [`CACHE_DIR`] is an input file plus a JSON manifest that records how
+//! it was generated (generator and its version, parameters, seed and the hashes of
+//! any source files)
+//! and the input's SHA-256. An input is only used when its manifest matches the
+//! request and the file still hashes to what the manifest says; anything else is
+//! regenerated, so stale or hand-edited files are never benchmarked against.
+//!
+//! Reference outputs live in [`REFERENCES_DIR`], keyed by challenge version, input
+//! hash and parameters, so they are reused across sessions and only recomputed when
+//! the input or the reference implementation changes.
+
+use crate::challenges::{Challenge, InputOptions};
+use crate::digest::ChunkedDigest;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Where cached inputs are stored.
+pub const CACHE_DIR: &str = "./input/cache";
+
+/// Where cached reference outputs are stored.
+pub const REFERENCES_DIR: &str = "./input/cache/references";
+
+/// How a cached input was generated, and what it hashes to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Manifest {
+    /// Name of the challenge whose generator produced the input.
+    pub generator: String,
+    /// The challenge's [`generator_version`](crate::ChallengeSpec::generator_version)
+    /// when the input was generated. Manifests from before it was recorded read as 0.
+    #[serde(default)]
+    pub generator_version: u32,
+    /// Every input parameter, including the defaults.
+    pub params: BTreeMap<String, u128>,
+    pub seed: u64,
+    /// SHA-256 of each file the generator reads, by path.
+    pub sources: BTreeMap<String, String>,
+    pub input_sha256: String,
+}
+
+/// Which reference implementation produced a cached output, from what, and what it
+/// hashes to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReferenceManifest {
+    pub challenge: String,
+    /// The challenge's [`version`](crate::ChallengeSpec::version) when the output was
+    /// computed.
+    pub version: u32,
+    /// Every input parameter, some of which (like the obelisk pull count) change the
+    /// answer for the same input.
+    pub params: BTreeMap<String, u128>,
+    pub input_sha256: String,
+    pub expected_output_sha256: String,
+    /// Chunked digest of the output, so it can be judged without being loaded.
+    pub digest: ChunkedDigest,
+}
+
+/// An input and its expected output, and where they came from.
+pub struct CachedInput {
+    pub input: String,
+    pub expected: Expected,
+    pub input_status: CacheStatus,
+    pub reference_status: CacheStatus,
+}
+
+/// The expected output, in full or only as a digest.
+#[derive(Debug, Clone)]
+pub enum Expected {
+    Output(String),
+    Digest(ChunkedDigest),
+}
+
+/// Whether a cached file could be used.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CacheStatus {
+    /// A valid entry was found.
+    Hit,
+    /// There was no entry; one was generated.
+    Miss,
+    /// The entry did not validate, for the given reason, and was regenerated.
+    Stale(String),
+}
+
+/// Loads the input for `challenge` generated with `options` and its reference output
+/// from the cache, generating and storing whichever is missing or stale. With
+/// `digest_only`, only the reference output's [`ChunkedDigest`] is returned and the
+/// output itself is not loaded.
+pub fn load_or_generate(
+    challenge: &dyn Challenge,
+    options: &InputOptions,
+    digest_only: bool,
+) -> Result<CachedInput, Box<dyn Error>> {
+    let params = options.resolved_params(challenge.spec());
+    let sources = hash_sources(challenge)?;
+    let input_entry = InputEntry::new(challenge, options.seed, &params);
+    let (input, input_status) = match input_entry.load(challenge, options.seed, &params, &sources) {
+        Ok(Some(input)) => (input, CacheStatus::Hit),
+        Ok(None) => (
+            generate_input(challenge, options, &input_entry, sources)?,
+            CacheStatus::Miss,
+        ),
+        Err(reason) => (
+            generate_input(challenge, options, &input_entry, sources)?,
+            CacheStatus::Stale(reason),
+        ),
+    };
+
+    let input_sha256 = sha256_hex(input.as_bytes());
+    let reference = ReferenceEntry::new(challenge, &input_sha256, &params);
+    let (expected, reference_status) = match reference.load(digest_only) {
+        Ok(Some(expected)) => (expected, CacheStatus::Hit),
+        found => {
+            let expected_output = challenge.expected_output(&input, options);
+            let manifest = ReferenceManifest {
+                challenge: challenge.name().to_string(),
+                version: challenge.spec().version,
+                params,
+                input_sha256,
+                expected_output_sha256: sha256_hex(expected_output.as_bytes()),
+                digest: ChunkedDigest::of(&expected_output),
+            };
+            reference
+                .store(&manifest, &expected_output)
+                .map_err(|err| {
+                    format!("failed to write {}: {err}", reference.manifest.display())
+                })?;
+            let status = match found {
+                Err(reason) => CacheStatus::Stale(reason),
+                _ => CacheStatus::Miss,
+            };
+            let expected = if digest_only {
+                Expected::Digest(manifest.digest)
+            } else {
+                Expected::Output(expected_output)
+            };
+            (expected, status)
+        }
+    };
+
+    Ok(CachedInput {
+        input,
+        expected,
+        input_status,
+        reference_status,
+    })
+}
+
+/// What recomputing one cached reference output found.
+pub struct ReferenceCheck {
+    pub manifest: ReferenceManifest,
+    pub result: ReferenceResult,
+}
+
+/// The outcome of recomputing a cached reference output.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReferenceResult {
+    /// The reference implementation still produces the cached output.
+    Match,
+    /// It now produces an output with a different hash.
+    Mismatch { recomputed_sha256: String },
+    /// The output was computed by an older version of the challenge.
+    Outdated,
+    /// The input it was computed from is no longer cached.
+    MissingInput,
+}
+
+/// Recomputes every cached reference output of `challenge` and compares it with the
+/// cached hash, to catch regressions in the reference implementation.
+pub fn verify_references(challenge: &dyn Challenge) -> Result<Vec<ReferenceCheck>, Box<dyn Error>> {
+    let references = read_manifests::<ReferenceManifest>(Path::new(REFERENCES_DIR))?;
+    let inputs = read_manifests::<Manifest>(Path::new(CACHE_DIR))?;
+    let mut checks = Vec::new();
+    for (_, manifest) in references {
+        if manifest.challenge != challenge.name() {
+            continue;
+        }
+        let result = if manifest.version != challenge.spec().version {
+            ReferenceResult::Outdated
+        } else {
+            let input = inputs
+                .iter()
+                .filter(|(_, input)| input.input_sha256 == manifest.input_sha256)
+                .find_map(|(path, input)| {
+                    let entry = InputEntry::from_manifest_path(path);
+                    read_verified(&entry.input, &input.input_sha256).ok()
+                });
+            match input {
+                Some(input) => {
+                    let mut options = InputOptions::default();
+                    for (name, value) in &manifest.params {
+                        options.set_param(challenge.spec(), name, *value)?;
+                    }
+                    let recomputed_sha256 =
+                        sha256_hex(challenge.expected_output(&input, &options).as_bytes());
+                    if recomputed_sha256 == manifest.expected_output_sha256 {
+                        ReferenceResult::Match
+                    } else {
+                        ReferenceResult::Mismatch { recomputed_sha256 }
+                    }
+                }
+                None => ReferenceResult::MissingInput,
+            }
+        };
+        checks.push(ReferenceCheck { manifest, result });
+    }
+    Ok(checks)
+}
+
+/// Hex-encoded SHA-256 of `bytes`.
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+/// Extension of manifest files.
+const MANIFEST_SUFFIX: &str = ".manifest.json";
+
+/// The files that make up one cached input.
+struct InputEntry {
+    manifest: PathBuf,
+    input: PathBuf,
+}
+
+impl InputEntry {
+    /// The entry for an input: named after the challenge and seed, plus a hash of the
+    /// parameters so every combination gets its own files.
+    fn new(challenge: &dyn Challenge, seed: u64, params: &BTreeMap<String, u128>) -> Self {
+        let stem = format!(
+            "{}-{}-{}",
+            challenge.name(),
+            seed,
+            &params_hash(params)[..12]
+        );
+        Self::from_manifest_path(&Path::new(CACHE_DIR).join(format!("{stem}{MANIFEST_SUFFIX}")))
+    }
+
+    fn from_manifest_path(manifest: &Path) -> Self {
+        InputEntry {
+            manifest: manifest.to_path_buf(),
+            input: sibling(manifest, ".input.txt"),
+        }
+    }
+
+    /// The cached input, `None` if there is no entry, or why the entry cannot be used.
+    fn load(
+        &self,
+        challenge: &dyn Challenge,
+        seed: u64,
+        params: &BTreeMap<String, u128>,
+        sources: &BTreeMap<String, String>,
+    ) -> Result<Option<String>, String> {
+        let Some(manifest) = read_manifest::<Manifest>(&self.manifest)? else {
+            return Ok(None);
+        };
+        if manifest.generator != challenge.name()
+            || manifest.seed != seed
+            || manifest.params != *params
+        {
+            return Err(String::from("generated with different parameters"));
+        }
+        if manifest.generator_version != challenge.spec().generator_version {
+            return Err(String::from(
+                "generated by another version of the generator",
+            ));
+        }
+        if manifest.sources != *sources {
+            return Err(String::from("the generator's source files changed"));
+        }
+        read_verified(&self.input, &manifest.input_sha256).map(Some)
+    }
+}
+
+/// The files that make up one cached reference output.
+struct ReferenceEntry {
+    manifest: PathBuf,
+    expected_output: PathBuf,
+    /// What the manifest must say for the output to be reused.
+    challenge: String,
+    version: u32,
+    input_sha256: String,
+    params: BTreeMap<String, u128>,
+}
+
+impl ReferenceEntry {
+    fn new(challenge: &dyn Challenge, input_sha256: &str, params: &BTreeMap<String, u128>) -> Self {
+        let version = challenge.spec().version;
+        let stem = format!(
+            "{}-v{}-{}-{}",
+            challenge.name(),
+            version,
+            &input_sha256[..12],
+            &params_hash(params)[..12]
+        );
+        let manifest = Path::new(REFERENCES_DIR).join(format!("{stem}{MANIFEST_SUFFIX}"));
+        ReferenceEntry {
+            expected_output: sibling(&manifest, ".expected.txt"),
+            manifest,
+            challenge: challenge.name().to_string(),
+            version,
+            input_sha256: input_sha256.to_string(),
+            params: params.clone(),
+        }
+    }
+
+    /// The cached output, or just its digest, `None` if there is no entry, or why the
+    /// entry cannot be used.
+    fn load(&self, digest_only: bool) -> Result<Option<Expected>, String> {
+        let Some(manifest) = read_manifest::<ReferenceManifest>(&self.manifest)? else {
+            return Ok(None);
+        };
+        if manifest.challenge != self.challenge
+            || manifest.version != self.version
+            || manifest.input_sha256 != self.input_sha256
+            || manifest.params != self.params
+        {
+            return Err(String::from("computed for a different input or version"));
+        }
+        if digest_only {
+            return Ok(Some(Expected::Digest(manifest.digest)));
+        }
+        read_verified(&self.expected_output, &manifest.expected_output_sha256)
+            .map(|output| Some(Expected::Output(output)))
+    }
+
+    /// Writes the entry, manifest last so a partly written entry is never loaded.
+    fn store(&self, manifest: &ReferenceManifest, expected_output: &str) -> std::io::Result<()> {
+        fs::create_dir_all(REFERENCES_DIR)?;
+        fs::write(&self.expected_output, expected_output)?;
+        write_manifest(&self.manifest, manifest)
+    }
+}
+
+/// Generates the input and stores it in `entry`, manifest last so a partly written
+/// entry is never loaded.
+fn generate_input(
+    challenge: &dyn Challenge,
+    options: &InputOptions,
+    entry: &InputEntry,
+    sources: BTreeMap<String, String>,
+) -> Result<String, Box<dyn Error>> {
+    let input = challenge.generate_input(options)?;
+    let manifest = Manifest {
+        generator: challenge.name().to_string(),
+        generator_version: challenge.spec().generator_version,
+        params: options.resolved_params(challenge.spec()),
+        seed: options.seed,
+        sources,
+        input_sha256: sha256_hex(input.as_bytes()),
+    };
+    fs::create_dir_all(CACHE_DIR)
+        .and_then(|()| fs::write(&entry.input, &input))
+        .and_then(|()| write_manifest(&entry.manifest, &manifest))
+        .map_err(|err| format!("failed to write {}: {err}", entry.manifest.display()))?;
+    Ok(input)
+}
+
+/// The file next to `manifest` with the same stem and the given suffix.
+fn sibling(manifest: &Path, suffix: &str) -> PathBuf {
+    let name = manifest.file_name().unwrap_or_default().to_string_lossy();
+    let stem = name.strip_suffix(MANIFEST_SUFFIX).unwrap_or(&name);
+    manifest.with_file_name(format!("{stem}{suffix}"))
+}
+
+/// Reads a manifest, `None` if it does not exist.
+fn read_manifest<T: for<'de> Deserialize<'de>>(path: &Path) -> Result<Option<T>, String> {
+    let Ok(contents) = fs::read_to_string(path) else {
+        return Ok(None);
+    };
+    serde_json::from_str(&contents)
+        .map(Some)
+        .map_err(|err| format!("unreadable manifest: {err}"))
+}
+
+/// Every readable manifest in `dir`, with its path.
+fn read_manifests<T: for<'de> Deserialize<'de>>(
+    dir: &Path,
+) -> Result<Vec<(PathBuf, T)>, Box<dyn Error>> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Ok(Vec::new());
+    };
+    let mut manifests = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        if !path.to_string_lossy().ends_with(MANIFEST_SUFFIX) {
+            continue;
+        }
+        if let Ok(Some(manifest)) = read_manifest(&path) {
+            manifests.push((path, manifest));
+        }
+    }
+    manifests.sort_by(|(a, _), (b, _)| a.cmp(b));
+    Ok(manifests)
+}
+
+fn write_manifest<T: Serialize>(path: &Path, manifest: &T) -> std::io::Result<()> {
+    let manifest = serde_json::to_string_pretty(manifest).map_err(std::io::Error::other)?;
+    fs::write(path, manifest)
+}
+
+/// Reads `path` and checks that it hashes to `sha256`.
+fn read_verified(path: &Path, sha256: &str) -> Result<String, String> {
+    let contents = fs::read_to_string(path).map_err(|err| format!("{}: {err}", path.display()))?;
+    if sha256_hex(contents.as_bytes()) != sha256 {
+        return Err(format!("{} does not match its hash", path.display()));
+    }
+    Ok(contents)
+}
+
+/// A hash of the parameters, to tell their cache entries apart.
+fn params_hash(params: &BTreeMap<String, u128>) -> String {
+    let params: Vec<String> = params
+        .iter()
+        .map(|(name, value)| format!("{name}={value}"))
+        .collect();
+    sha256_hex(params.join(",").as_bytes())
+}
+
+/// SHA-256 of each source file of `challenge`'s generator.
+fn hash_sources(challenge: &dyn Challenge) -> Result<BTreeMap<String, String>, Box<dyn Error>> {
+    let mut hashes = BTreeMap::new();
+    for source in challenge.spec().sources {
+        let contents = fs::read(source).map_err(|err| format!("failed to read {source}: {err}"))?;
+        hashes.insert(source.to_string(), sha256_hex(&contents));
+    }
+    Ok(hashes)
+}
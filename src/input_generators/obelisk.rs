@@ -1,19 +1,29 @@
 use rand::prelude::*;
+use rand::rngs::StdRng;
+use std::ops::RangeInclusive;
 
-pub fn gen_obelisks() -> String {
+/// How many random stones are added to the fixed ones by default.
+pub const RANDOM_STONE_COUNT: usize = 3;
+
+/// Range the random stones are drawn from by default.
+pub const RANDOM_STONE_RANGE: RangeInclusive<u128> = 4126..=8920;
+
+/// Generates a space-separated arrangement of obelisks: a fixed set of stones plus
+/// `random_count` stones drawn from `range`, shuffled using `seed`.
+pub fn gen_obelisks(seed: u64, random_count: usize, range: RangeInclusive<u128>) -> String {
     let mut numbers: Vec<u128> = vec![2, 72, 8949, 0, 981038, 86311]; // initial numbers
-    let mut rng = rand::rng();
+    // Initialize a seeded RNG for reproducible results.
+    let mut rng = StdRng::seed_from_u64(seed);
 
-    // Generate and push three random u64 numbers
-    numbers.extend((0..3).map(|_| rand::random_range(4126..8921)));
+    // Generate and push the random stones
+    numbers.extend((0..random_count).map(|_| rng.random_range(range.clone())));
 
     // Shuffle the vector
     numbers.shuffle(&mut rng);
 
-    let stones = numbers
+    numbers
         .iter()
         .map(|stone| stone.to_string())
         .collect::<Vec<String>>()
-        .join(" ");
-    stones
+        .join(" ")
 }
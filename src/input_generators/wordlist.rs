@@ -1,15 +1,17 @@
-use super::util::format_usize;
 use rand::rngs::StdRng;
 use rand::{Rng, SeedableRng};
 use std::error::Error;
 use std::fs::File;
-use std::io::{BufRead, BufReader, Write};
+use std::io::{BufRead, BufReader};
 
-const RNG_SEED: u64 = 9001; // Used for generating repeatable wordlists without syncing the files over git
+/// The words that wordlists are drawn from.
+pub const WORD_SOURCE: &str = "./input/words_alpha.txt";
 
-pub fn wordlist(n: usize) -> Result<String, Box<dyn Error>> {
+/// Generates a space-separated list of `n` random words from "words_alpha.txt",
+/// picked using `seed`.
+pub fn wordlist_seeded(n: usize, seed: u64) -> Result<String, Box<dyn Error>> {
     // Read words from "words_alpha.txt" into a vector
-    let file = File::open("./input/words_alpha.txt")?;
+    let file = File::open(WORD_SOURCE)?;
     let reader = BufReader::new(file);
     let mut word_list = Vec::new();
     for line in reader.lines() {
@@ -19,7 +21,6 @@ pub fn wordlist(n: usize) -> Result<String, Box<dyn Error>> {
     }
 
     // Initialize a seeded RNG for reproducible results.
-    let seed: u64 = RNG_SEED;
     let mut rng = StdRng::seed_from_u64(seed);
     // A rough heuristic capacity estimation for the output string.
     let mut out_str = String::with_capacity(n * 6);
@@ -34,13 +35,5 @@ pub fn wordlist(n: usize) -> Result<String, Box<dyn Error>> {
 
     // Remove the trailing space.
     out_str.pop();
-    let num_words_str = format_usize(n);
-    let fpath = format!("input/wordlist_{num_words_str}.txt");
-
-    // Write the output string to "custom_wordlist.txt"
-    let mut output_file = File::create(fpath)?;
-    output_file.write_all(out_str.as_bytes())?;
-
-    println!("Successfully generated wordlist.");
     Ok(out_str)
 }
@@ -1,29 +1,27 @@
+pub mod challenges;
+pub mod checker;
+pub mod complexity;
+pub mod digest;
+pub mod history;
+pub mod html;
+pub mod input_cache;
 pub mod input_generators;
+pub mod mismatch;
+pub mod report;
+pub mod runner;
+pub mod stats;
+
+pub use challenges::{CHALLENGES, Challenge, ChallengeSpec, InputOptions, Param, find_challenge};
+pub use checker::Checker;
+pub use digest::{ChunkMismatch, ChunkedDigest, StreamingVerifier};
+pub use mismatch::{IntegerAnswer, Mismatch, compare_integer_answer, first_mismatch};
+pub use runner::{
+    Limits, MULTI_THREADED_CPU_RATIO, ResourceUsage, RunOutcome, STDERR_LIMIT, Threading, Verdict,
+    run_executable, run_executable_streaming,
+};
 
 use rayon::prelude::*;
 use std::collections::{HashMap, HashSet};
-use std::io::Write;
-use std::path::Path;
-use std::process::{Command, Stdio};
-
-/// Runs an external executable by piping `input` to its stdin and capturing stdout.
-pub fn run_executable(path: &Path, input: &str) -> String {
-    let mut child = Command::new(path)
-        .stdin(Stdio::piped())
-        .stdout(Stdio::piped())
-        .spawn()
-        .expect("Failed to spawn process");
-
-    {
-        let child_stdin = child.stdin.as_mut().expect("Failed to open stdin");
-        child_stdin
-            .write_all(input.as_bytes())
-            .expect("Failed to write input to child process");
-    }
-
-    let output = child.wait_with_output().expect("Failed to capture output");
-    String::from_utf8_lossy(&output.stdout).into_owned()
-}
 
 /// Helper to format time nicely. If less than one second, print in milliseconds.
 pub fn format_time(seconds: f64) -> String {
@@ -34,6 +32,22 @@ pub fn format_time(seconds: f64) -> String {
     }
 }
 
+/// Helper to format a byte count using binary units, e.g. `12.3 MiB`.
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
 /// Reference implementation for the 'reverse' challenge.
 pub fn reference_reverse_old(input: &str) -> String {
     input
@@ -234,7 +248,7 @@ pub fn reference_obelisk_count(obelisks: &[u128], pull_count: usize) -> u128 {
             vec![3, 2, 3, 2]
         } else {
             let dcount = digit_count(n);
-            if dcount % 2 == 0 {
+            if dcount.is_multiple_of(2) {
                 // rule #2 (even digit count): split
                 let (l, r) = split_obelisk_two(n);
                 vec![l, r]
@@ -1,189 +1,1695 @@
+use clap::builder::{PossibleValuesParser, TypedValueParser};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use std::env;
+use std::error::Error;
+use std::ffi::OsStr;
+use std::fmt;
 use std::fs;
-use std::time::Instant;
-use uiasub_code_benchmark::input_generators::gcd_numbers::generate_gcd_numbers;
-use uiasub_code_benchmark::input_generators::gcd_numbers::generate_gcd_numbers_large_capacity;
-use uiasub_code_benchmark::input_generators::obelisk::gen_obelisks;
-use uiasub_code_benchmark::input_generators::wordlist::wordlist;
-use uiasub_code_benchmark::reference_gcd_large_capacity;
-use uiasub_code_benchmark::reference_obelisk_count;
-use uiasub_code_benchmark::{format_time, reference_gcd, reference_reverse, run_executable};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use uiasub_code_benchmark::challenges::{
+    CHALLENGES, find_challenge, format_param_value, params_markdown, seed_for_run,
+};
+use uiasub_code_benchmark::complexity::{ClassFit, PowerFit, best_fit, fit_power_law};
+use uiasub_code_benchmark::history::{
+    HISTORY_FILE, HistoryRecord, Machine, Version, load_history, record_session, versions,
+};
+use uiasub_code_benchmark::html::{HTML_FILE, render as render_html};
+use uiasub_code_benchmark::input_cache::{
+    CACHE_DIR, CacheStatus, Expected, ReferenceResult, load_or_generate, sha256_hex,
+    verify_references,
+};
+use uiasub_code_benchmark::report::{
+    ExecutableReport, LEADERBOARD_FILE, RunRecord, ScalePoint, ScaleReport, SessionReport,
+};
+use uiasub_code_benchmark::stats::{
+    Interval, mann_whitney_u, median, median_ci, median_ratio_ci, summarize,
+};
+use uiasub_code_benchmark::{
+    Challenge, Checker, ChunkMismatch, ChunkedDigest, InputOptions, Limits, RunOutcome,
+    STDERR_LIMIT, StreamingVerifier, Threading, Verdict, format_bytes, format_time, run_executable,
+    run_executable_streaming,
+};
+
+/// How many lines of stderr to show for a failed run.
+const STDERR_TAIL_LINES: usize = 10;
+
+/// Where benchmark sessions store their logs and summaries.
+const RESULTS_DIR: &str = "./results";
+
+/// Name of the plain-text summary written to each session directory.
+const SUMMARY_FILE: &str = "summary.txt";
+
+/// The most measured runs in adaptive mode, unless `-n` says otherwise.
+const ADAPTIVE_MAX_RUNS: u32 = 1000;
+
+/// p-value below which `compare` and `ab` call a difference in timings significant.
+const SIGNIFICANCE: f64 = 0.05;
+
+/// How many times the reference implementation is timed for the leaderboard.
+const REFERENCE_RUNS: usize = 3;
+
+/// Adaptive mode does not trust a confidence interval from fewer runs than this.
+const ADAPTIVE_MIN_RUNS: usize = 5;
+
+/// Adaptive mode recomputes the bootstrap confidence interval only once the run count
+/// has grown by about `1 / CI_RECHECK_DIVISOR` since the last check, so the number of
+/// checks grows logarithmically with the number of runs.
+const CI_RECHECK_DIVISOR: usize = 10;
+
+/// The README whose parameter table `readme --write` regenerates.
+const README_FILE: &str = "./README.md";
+
+/// Markers around the generated parameter table in the README.
+const PARAMS_START: &str = "<!-- BEGIN GENERATED PARAMETERS -->";
+const PARAMS_END: &str = "<!-- END GENERATED PARAMETERS -->";
+
+/// Benchmark and judge submissions to the UiA SUB coding challenges.
+#[derive(Parser)]
+#[command(version)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Benchmark every submission for a challenge.
+    Run(RunArgs),
+    /// List the available challenges.
+    #[command(long_flag = "list")]
+    List,
+    /// Write a challenge's input to a file, or to stdout.
+    Generate {
+        #[arg(value_parser = challenge_parser())]
+        challenge: &'static dyn Challenge,
+        #[command(flatten)]
+        input: InputArgs,
+        /// File to write the input to instead of stdout.
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+    },
+    /// Run a single executable once and check its answer.
+    Verify {
+        #[arg(value_parser = challenge_parser())]
+        challenge: &'static dyn Challenge,
+        /// Path to the executable to check.
+        executable: PathBuf,
+        #[command(flatten)]
+        input: InputArgs,
+        #[command(flatten)]
+        limits: LimitArgs,
+    },
+    /// Race two executables head to head: interleave their runs in random order on the
+    /// same input and test whether one is faster.
+    Ab(AbArgs),
+    /// Run one executable at a geometric series of input sizes and estimate how its
+    /// time and memory grow.
+    Scale(ScaleArgs),
+    /// Show the summary of a previous benchmark session.
+    Report {
+        /// Session directory to show; defaults to the most recent one.
+        session: Option<PathBuf>,
+    },
+    /// Rank the executables of a previous benchmark session, fastest correct one first.
+    Leaderboard {
+        /// Session directory to rank; defaults to the most recent one.
+        session: Option<PathBuf>,
+    },
+    /// Compare the latest version of each submission with its previous versions, using
+    /// the results history of this machine.
+    Compare {
+        #[arg(value_parser = challenge_parser())]
+        challenge: &'static dyn Challenge,
+        /// File name of the submission to compare; defaults to every one with history.
+        executable: Option<String>,
+        /// Only compare sessions run with this input parameter, as NAME=VALUE; parameters
+        /// not given must have been at their defaults. Can be repeated.
+        #[arg(short = 'p', long = "param", value_name = "NAME=VALUE", value_parser = parse_param)]
+        params: Vec<(String, u128)>,
+    },
+    /// Write the HTML report of a previous benchmark session again, e.g. to pick up
+    /// newer scaling curves.
+    Html {
+        /// Session directory to report on; defaults to the most recent one.
+        session: Option<PathBuf>,
+    },
+    /// Recompute the cached reference outputs and check they have not changed.
+    VerifyReferences {
+        /// Challenge to check; defaults to all of them.
+        #[arg(value_parser = challenge_parser())]
+        challenge: Option<&'static dyn Challenge>,
+    },
+    /// Print the README's table of challenge parameters.
+    Readme {
+        /// Update the table in README.md instead of printing it.
+        #[arg(long)]
+        write: bool,
+    },
+}
+
+#[derive(Args)]
+struct AbArgs {
+    #[arg(value_parser = challenge_parser())]
+    challenge: &'static dyn Challenge,
+    /// Path to the first executable, A.
+    a: PathBuf,
+    /// Path to the second executable, B.
+    b: PathBuf,
+    /// Number of runs of each executable.
+    #[arg(short = 'n', long, default_value_t = 10, value_parser = clap::value_parser!(u32).range(2..))]
+    runs: u32,
+    #[command(flatten)]
+    input: InputArgs,
+    #[command(flatten)]
+    limits: LimitArgs,
+}
+
+#[derive(Args)]
+struct ScaleArgs {
+    #[arg(value_parser = challenge_parser())]
+    challenge: &'static dyn Challenge,
+    /// Path to the executable to measure.
+    executable: PathBuf,
+    /// Parameter to grow [default: the challenge's size, e.g. `words` for reverse].
+    #[arg(long, value_name = "NAME")]
+    grow: Option<String>,
+    /// Grow the parameter's bit width instead of its value: sizes are bit widths, and
+    /// the parameter is set to the largest value of that width, e.g. `--grow max_value
+    /// --bits` for gcd.
+    #[arg(long)]
+    bits: bool,
+    /// Smallest size [default: the parameter's default / 16, or 8 bits].
+    #[arg(long, value_name = "SIZE", value_parser = parse_value)]
+    from: Option<u128>,
+    /// Largest size [default: the parameter's default, or its bit width].
+    #[arg(long, value_name = "SIZE", value_parser = parse_value)]
+    to: Option<u128>,
+    /// Ratio between consecutive sizes.
+    #[arg(long, default_value_t = 2.0, value_parser = parse_factor)]
+    factor: f64,
+    /// Number of runs at each size; the median time and highest peak memory are kept.
+    #[arg(short = 'n', long, default_value_t = 3, value_parser = clap::value_parser!(u32).range(1..))]
+    runs: u32,
+    #[command(flatten)]
+    input: InputArgs,
+    #[command(flatten)]
+    limits: LimitArgs,
+}
+
+#[derive(Args)]
+struct RunArgs {
+    #[arg(value_parser = challenge_parser())]
+    challenge: &'static dyn Challenge,
+    /// Number of measured runs per submission; in adaptive mode, the most runs to make
+    /// [default: 1, or 1000 in adaptive mode].
+    #[arg(short = 'n', long, value_parser = clap::value_parser!(u32).range(1..))]
+    runs: Option<u32>,
+    /// Runs before the measured ones that are judged but left out of the statistics.
+    #[arg(long, value_name = "RUNS", default_value_t = 0)]
+    warmup: u32,
+    /// Adaptive mode: keep running until the 95% confidence interval of the median is
+    /// narrower than this percentage of the median.
+    #[arg(long, value_name = "PERCENT", value_parser = parse_percent)]
+    target_ci: Option<f64>,
+    /// Adaptive mode: stop once a submission's measured runs have taken this long.
+    #[arg(long, value_name = "SECONDS", value_parser = parse_seconds)]
+    time_budget: Option<Duration>,
+    #[command(flatten)]
+    input: InputArgs,
+    /// Generate fresh input from a new seed for the whole session or for every run,
+    /// so hardcoded answers fail. Seeds are random unless `--seed` is given.
+    #[arg(long, value_name = "WHEN")]
+    fresh_inputs: Option<FreshInputs>,
+    /// Judge output while it streams in, against chunked digests of the expected
+    /// output, so memory stays bounded however large the output is.
+    #[arg(long)]
+    stream: bool,
+    #[command(flatten)]
+    limits: LimitArgs,
+}
+
+/// How often `run` draws a new input seed.
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum FreshInputs {
+    /// One seed for the whole session.
+    Session,
+    /// Consecutive seeds, one per run, shared by every executable.
+    Run,
+}
+
+/// Options that shape the generated challenge input.
+#[derive(Args)]
+struct InputArgs {
+    /// Seed for the input generator [default: 9001].
+    #[arg(long)]
+    seed: Option<u64>,
+    /// Override an input parameter, e.g. `-p pairs=1000`; see `list` for each
+    /// challenge's parameters. Can be repeated.
+    #[arg(short = 'p', long = "param", value_name = "NAME=VALUE", value_parser = parse_param)]
+    params: Vec<(String, u128)>,
+}
+
+impl InputArgs {
+    /// Applies the overrides to `challenge`'s default input options.
+    fn options(&self, challenge: &dyn Challenge) -> Result<InputOptions, String> {
+        let mut options = InputOptions::default();
+        if let Some(seed) = self.seed {
+            options.seed = seed;
+        }
+        for (name, value) in &self.params {
+            options.set_param(challenge.spec(), name, *value)?;
+        }
+        Ok(options)
+    }
+}
+
+/// Overrides for a challenge's default resource limits.
+#[derive(Args)]
+struct LimitArgs {
+    /// Wall-clock time limit per run, in seconds [default: depends on the challenge].
+    #[arg(short = 't', long = "time-limit", value_name = "SECONDS", value_parser = parse_seconds, allow_negative_numbers = true)]
+    time_limit: Option<Duration>,
+    /// Peak memory (resident set size) limit per run, in MiB [default: no limit]. The
+    /// kernel also caps each process's data segment (RLIMIT_DATA) at this size, which
+    /// counts allocated virtual memory rather than resident memory.
+    #[arg(short = 'm', long = "memory-limit", value_name = "MIB", value_parser = clap::value_parser!(u64).range(1..=u64::MAX >> 20))]
+    memory_limit: Option<u64>,
+}
+
+impl LimitArgs {
+    /// Applies the overrides to `challenge`'s default limits.
+    fn limits_for(&self, challenge: &dyn Challenge) -> Limits {
+        let mut limits = challenge.limits();
+        if let Some(time) = self.time_limit {
+            limits.time = time;
+        }
+        if let Some(mib) = self.memory_limit {
+            limits.memory_bytes = Some(mib * 1024 * 1024);
+        }
+        limits
+    }
+}
+
+/// Accepts exactly the registered challenge names.
+fn challenge_parser() -> impl TypedValueParser<Value = &'static dyn Challenge> {
+    PossibleValuesParser::new(CHALLENGES.iter().map(|challenge| challenge.name()))
+        .map(|name| find_challenge(&name).expect("possible values are challenge names"))
+}
+
+/// Parses a positive percentage into a fraction.
+fn parse_percent(value: &str) -> Result<f64, String> {
+    match value.trim_end_matches('%').parse::<f64>() {
+        Ok(percent) if percent > 0.0 && percent.is_finite() => Ok(percent / 100.0),
+        _ => Err(String::from("expected a positive percentage")),
+    }
+}
+
+/// Parses a positive number of seconds that fits in a [`Duration`].
+fn parse_seconds(value: &str) -> Result<Duration, String> {
+    match value.parse::<f64>().map(Duration::try_from_secs_f64) {
+        Ok(Ok(duration)) if !duration.is_zero() => Ok(duration),
+        _ => Err(String::from("expected a positive number of seconds")),
+    }
+}
+
+/// Parses `NAME=VALUE`, where the value may use `_` or `,` as digit separators or be
+/// `u128::MAX`.
+fn parse_param(value: &str) -> Result<(String, u128), String> {
+    let (name, number) = value
+        .split_once('=')
+        .ok_or_else(|| String::from("expected NAME=VALUE"))?;
+    Ok((name.trim().to_string(), parse_value(number)?))
+}
+
+/// Parses a parameter value, which may use `_` or `,` as digit separators or be
+/// `u128::MAX`.
+fn parse_value(number: &str) -> Result<u128, String> {
+    let number = number.trim();
+    if number == "u128::MAX" {
+        return Ok(u128::MAX);
+    }
+    number
+        .replace(['_', ','], "")
+        .parse::<u128>()
+        .map_err(|err| format!("invalid value {number:?}: {err}"))
+}
+
+/// Parses the ratio between consecutive sizes of a `scale` series.
+fn parse_factor(value: &str) -> Result<f64, String> {
+    match value.parse::<f64>() {
+        Ok(factor) if factor > 1.0 && factor.is_finite() => Ok(factor),
+        _ => Err(String::from("expected a number greater than 1")),
+    }
+}
 
 fn main() {
-    // Usage: benchmark <challenge> [-n <num_runs>]
-    let args: Vec<String> = env::args().collect();
-    if args.len() < 2 {
-        eprintln!(
-            "Usage: {} [reverse|reverse_large|gcd|gcd_hard|obelisk|obelisk_hard] [-n <num_runs>]",
-            args[0]
-        );
+    // `benchmark <challenge> ...` is shorthand for `benchmark run <challenge> ...`.
+    let mut args: Vec<String> = env::args().collect();
+    if args.get(1).is_some_and(|arg| find_challenge(arg).is_some()) {
+        args.insert(1, String::from("run"));
+    }
+
+    let result = match Cli::parse_from(args).command {
+        Command::Run(args) => run(args),
+        Command::List => {
+            list();
+            Ok(())
+        }
+        Command::Generate {
+            challenge,
+            input,
+            output,
+        } => input
+            .options(challenge)
+            .map_err(Into::into)
+            .and_then(|options| generate(challenge, &options, output)),
+        Command::Verify {
+            challenge,
+            executable,
+            input,
+            limits,
+        } => input
+            .options(challenge)
+            .map_err(Into::into)
+            .and_then(|options| verify(challenge, &executable, &options, &limits)),
+        Command::Ab(args) => ab(args),
+        Command::Scale(args) => scale(args),
+        Command::Report { session } => report(session),
+        Command::Leaderboard { session } => leaderboard(session),
+        Command::Html { session } => html(session),
+        Command::Compare {
+            challenge,
+            executable,
+            params,
+        } => compare(challenge, executable.as_deref(), &params),
+        Command::VerifyReferences { challenge } => check_references(challenge),
+        Command::Readme { write } => readme(write),
+    };
+    if let Err(err) = result {
+        eprintln!("Error: {err}");
         std::process::exit(1);
     }
-    let challenge = &args[1];
-
-    // Default number of runs is 1; override using -n <number>
-    let mut num_runs: u32 = 1;
-    let mut i = 2;
-    while i < args.len() {
-        if args[i] == "-n" {
-            if i + 1 < args.len() {
-                num_runs = args[i + 1]
-                    .parse()
-                    .expect("Invalid number provided after -n");
-                i += 2;
-            } else {
-                eprintln!("Expected a number after -n");
-                std::process::exit(1);
+}
+
+fn list() {
+    for challenge in CHALLENGES {
+        let params: Vec<String> = challenge
+            .spec()
+            .params
+            .iter()
+            .map(|param| format!("{}={}", param.name, format_param_value(param.default)))
+            .collect();
+        println!(
+            "{:<14} {} ({})",
+            challenge.name(),
+            challenge.description(),
+            params.join(", ")
+        );
+    }
+}
+
+fn generate(
+    challenge: &dyn Challenge,
+    options: &InputOptions,
+    output: Option<PathBuf>,
+) -> Result<(), Box<dyn Error>> {
+    let input = challenge.generate_input(options)?;
+    match output {
+        Some(path) => fs::write(&path, input)
+            .map_err(|err| format!("failed to write {}: {err}", path.display()))?,
+        None => println!("{input}"),
+    }
+    Ok(())
+}
+
+fn verify(
+    challenge: &dyn Challenge,
+    executable: &Path,
+    options: &InputOptions,
+    limits: &LimitArgs,
+) -> Result<(), Box<dyn Error>> {
+    if !executable.is_file() {
+        return Err(format!("{} is not a file", executable.display()).into());
+    }
+    let mut inputs = Inputs::new(challenge, options.clone(), false, true, false);
+    let session_dir = new_session_dir(challenge)?;
+    let result = benchmark_executable(
+        executable,
+        &mut inputs,
+        &limits.limits_for(challenge),
+        &RunPlan::fixed(1),
+        &session_dir,
+    );
+    // Only keep the directory if a stderr log was saved in it.
+    let _ = fs::remove_dir(&session_dir);
+    let result = result?;
+    if result.report.all_accepted {
+        Ok(())
+    } else {
+        Err(format!("{} was not accepted", executable.display()).into())
+    }
+}
+
+fn run(args: RunArgs) -> Result<(), Box<dyn Error>> {
+    let challenge = args.challenge;
+    let mut options = args.input.options(challenge)?;
+    if args.stream && !matches!(challenge.checker(), Checker::Tokens) {
+        return Err(format!(
+            "--stream only works for challenges judged token by token, not {}",
+            challenge.name()
+        )
+        .into());
+    }
+    if args.fresh_inputs.is_some() && args.input.seed.is_none() {
+        options.seed = rand::random();
+    }
+    let plan = RunPlan {
+        warmup: args.warmup,
+        max_runs: args.runs.unwrap_or(1),
+        target_ci: args.target_ci,
+        time_budget: args.time_budget,
+    };
+    let plan = match args.runs {
+        None if plan.is_adaptive() => RunPlan {
+            max_runs: ADAPTIVE_MAX_RUNS,
+            ..plan
+        },
+        _ => plan,
+    };
+    let per_run = args.fresh_inputs == Some(FreshInputs::Run);
+    let seeds = if per_run && plan.is_adaptive() {
+        format!("seeds {}.. (one per run)", options.seed)
+    } else if per_run {
+        format!(
+            "seeds {}..={}",
+            options.seed,
+            seed_for_run(options.seed, plan.max_runs)
+        )
+    } else {
+        format!("seed {}", options.seed)
+    };
+    println!("Input {seeds}");
+    // Fresh inputs are one-offs, so only the fixed-seed input is worth caching.
+    let mut inputs = Inputs::new(
+        challenge,
+        options,
+        per_run,
+        args.fresh_inputs.is_none(),
+        args.stream,
+    );
+    // Prepare the first input up front, so generation problems show before any benchmarking.
+    inputs.for_run(1)?;
+    let limits = args.limits.limits_for(challenge);
+    let started_at = unix_time();
+    let session_dir = new_session_dir(challenge)?;
+
+    // Iterate over executables in the given directory.
+    let entries = fs::read_dir(challenge.executables_dir()).map_err(|err| {
+        format!(
+            "failed to read executables directory {}: {err}",
+            challenge.executables_dir()
+        )
+    })?;
+    let mut results = Vec::new();
+    for entry in entries {
+        let entry = entry?;
+        if entry.file_name() == "PUT YOUR .EXE FILE IN THIS FOLDER.md" {
+            continue;
+        }
+        let path = entry.path();
+        if path.is_file() {
+            let result = benchmark_executable(&path, &mut inputs, &limits, &plan, &session_dir)?;
+            results.push(result);
+        }
+    }
+
+    if results.is_empty() {
+        println!("No executables found in {}.", challenge.executables_dir());
+        return Ok(());
+    }
+    fs::create_dir_all(&session_dir)?;
+    let mut summary = format!("Challenge: {} ({}, {})\n", challenge.name(), plan, seeds);
+    for result in &results {
+        summary.push_str(&result.summary);
+        summary.push('\n');
+    }
+    fs::write(session_dir.join(SUMMARY_FILE), summary)?;
+    let reference_time = time_reference(&mut inputs)?;
+    let report = SessionReport {
+        challenge: challenge.name().to_string(),
+        challenge_version: challenge.spec().version,
+        params: inputs.options.resolved_params(challenge.spec()),
+        seed: inputs.options.seed,
+        fresh_inputs: args.fresh_inputs.map(|fresh| match fresh {
+            FreshInputs::Session => String::from("session"),
+            FreshInputs::Run => String::from("run"),
+        }),
+        warmup_runs: plan.warmup,
+        time_limit_secs: limits.time.as_secs_f64(),
+        memory_limit_bytes: limits.memory_bytes,
+        started_at,
+        reference_time_secs: Some(reference_time),
+        executables: results.into_iter().map(|result| result.report).collect(),
+    };
+    report.save(&session_dir)?;
+    let html_path = session_dir.join(HTML_FILE);
+    fs::write(&html_path, render_html(&report, &scale_reports(&report)))?;
+    if let Err(err) = record_session(&report, &session_dir, &Machine::current()) {
+        eprintln!("Warning: failed to record the session in {HISTORY_FILE}: {err}");
+    }
+    print!("\n{}", report.leaderboard_table());
+    println!("HTML report saved to {}", html_path.display());
+    println!("Session saved to {}", session_dir.display());
+    Ok(())
+}
+
+/// Runs A and B `args.runs` times each, in a random order within every round so
+/// background noise hits both alike, and reports how much faster A is.
+fn ab(args: AbArgs) -> Result<(), Box<dyn Error>> {
+    let challenge = args.challenge;
+    let options = args.input.options(challenge)?;
+    let contenders = [("A", &args.a), ("B", &args.b)];
+    for (_, path) in contenders {
+        if !path.is_file() {
+            return Err(format!("{} is not a file", path.display()).into());
+        }
+    }
+    let limits = args.limits.limits_for(challenge);
+    let checker = challenge.checker();
+    // The run order is drawn from the input seed, so a session can be replayed.
+    let mut rng = StdRng::seed_from_u64(options.seed);
+    println!("Input seed {}", options.seed);
+    let mut inputs = Inputs::new(challenge, options, false, true, false);
+    let (_, input, expected) = inputs.for_run(1)?;
+    for (label, path) in contenders {
+        println!("{label}: {}", path.display());
+    }
+
+    let mut times = [Vec::new(), Vec::new()];
+    for round in 1..=args.runs {
+        let mut order = [0, 1];
+        if rng.random_bool(0.5) {
+            order.swap(0, 1);
+        }
+        let mut line = format!("Round {round}:");
+        for i in order {
+            let (label, path) = contenders[i];
+            let (outcome, verdict, _) = judge(path, input, expected, &checker, &limits);
+            if !verdict.is_accepted() {
+                return Err(format!(
+                    "{label} ({}) failed round {round}: {verdict}",
+                    path.display()
+                )
+                .into());
             }
-        } else {
-            i += 1;
+            let duration_secs = outcome.elapsed.as_secs_f64();
+            times[i].push(duration_secs);
+            line.push_str(&format!(" {label} {}", format_time(duration_secs)));
         }
+        println!("{line}");
     }
 
-    // Load input data from the file.
-    let input;
+    let [a, b] = &times;
+    println!();
+    for (label, samples) in [("A", a), ("B", b)] {
+        println!(
+            "{label}: median {} (95% CI {})",
+            format_time(median(samples)),
+            format_interval(median_ci(samples))
+        );
+    }
+    // Median of B over median of A: above 1 means A is faster.
+    let speedup = median(b) / median(a);
+    let ci = median_ratio_ci(b, a);
+    let test = mann_whitney_u(a, b).ok_or("no runs to compare")?;
+    println!(
+        "\x1b[33mSpeedup of A over B: {speedup:.3}x (95% CI [{:.3}x, {:.3}x])\x1b[0m",
+        ci.low, ci.high
+    );
+    let conclusion = if test.p_value >= SIGNIFICANCE {
+        String::from("no significant difference")
+    } else if speedup > 1.0 {
+        String::from("\x1b[32mA is significantly faster\x1b[0m")
+    } else {
+        String::from("\x1b[32mB is significantly faster\x1b[0m")
+    };
+    println!(
+        "Mann–Whitney U = {}, p = {:.4}: {conclusion}",
+        test.u, test.p_value
+    );
+    Ok(())
+}
+
+/// Runs `args.executable` at every size of a geometric series, then fits how its time
+/// and peak memory grow with the size.
+fn scale(args: ScaleArgs) -> Result<(), Box<dyn Error>> {
+    let challenge = args.challenge;
+    let spec = challenge.spec();
+    if !args.executable.is_file() {
+        return Err(format!("{} is not a file", args.executable.display()).into());
+    }
+    let options = args.input.options(challenge)?;
+    let param_name = args.grow.as_deref().unwrap_or(spec.size_param);
+    let param = spec
+        .param(param_name)
+        .ok_or_else(|| format!("{} has no parameter {param_name:?}", challenge.name()))?;
+    let (default_from, default_to) = if args.bits {
+        (8, u128::from(u128::BITS - param.default.leading_zeros()))
+    } else {
+        ((param.default / 16).max(param.min), param.default)
+    };
+    let (from, to) = (
+        args.from.unwrap_or(default_from),
+        args.to.unwrap_or(default_to),
+    );
+    if from == 0 || from > to {
+        return Err(format!("expected 0 < --from <= --to, got {from} and {to}").into());
+    }
+    if args.bits && to > u128::from(u128::BITS) {
+        return Err(format!("bit widths go up to {}, got {to}", u128::BITS).into());
+    }
+    let sizes = geometric_series(from, to, args.factor);
+    let value_of = |size: u128| match size {
+        bits if args.bits && bits == u128::from(u128::BITS) => u128::MAX,
+        bits if args.bits => (1 << bits) - 1,
+        size => size,
+    };
+    // Check every size is in range before spending time on any of them.
+    for &size in &sizes {
+        options
+            .clone()
+            .set_param(spec, param.name, value_of(size))?;
+    }
 
-    // Compute expected output and set the executables directory.
-    let (expected_output, executables_dir) = match challenge.as_str() {
-        "reverse" => {
-            input = match fs::read_to_string("./input/wordlist_20K.txt") {
-                Ok(s) => s,
-                Err(_) => wordlist(20_000).expect("Failed to write custom wordlist."),
+    let unit = if args.bits {
+        format!("bits of {}", param.name)
+    } else {
+        String::from(param.name)
+    };
+    println!(
+        "Scaling {} on {} over {} = {}, {} runs each",
+        args.executable.display(),
+        challenge.name(),
+        unit,
+        sizes
+            .iter()
+            .map(|&size| format_param_value(size))
+            .collect::<Vec<String>>()
+            .join(", "),
+        args.runs
+    );
+    let limits = args.limits.limits_for(challenge);
+    let checker = challenge.checker();
+    let mut points = Vec::new();
+    for &size in &sizes {
+        let mut options = options.clone();
+        options.set_param(spec, param.name, value_of(size))?;
+        let mut inputs = Inputs::new(challenge, options, false, true, false);
+        let (_, input, expected) = inputs.for_run(1)?;
+        let mut times = Vec::new();
+        let mut peak_rss = Some(0);
+        for _ in 0..args.runs {
+            let (outcome, verdict, _) = judge(&args.executable, input, expected, &checker, &limits);
+            if !verdict.is_accepted() {
+                println!(
+                    "\x1b[31m❌ {verdict} at {unit} = {}; stopping here.\x1b[0m",
+                    format_param_value(size)
+                );
+                break;
+            }
+            times.push(outcome.elapsed.as_secs_f64());
+            // One run too short to measure leaves the peak at this size unknown, as the
+            // others alone could understate it.
+            peak_rss = peak_rss
+                .zip(outcome.usage.and_then(|usage| usage.peak_rss_bytes))
+                .map(|(peak, rss)| peak.max(rss));
+        }
+        if times.len() < args.runs as usize {
+            break;
+        }
+        let point = ScalePoint {
+            size,
+            param_value: value_of(size),
+            median_time_secs: median(&times),
+            peak_rss_bytes: peak_rss,
+        };
+        println!(
+            "{unit} = {}: median {}, peak RSS {}",
+            format_param_value(size),
+            format_time(point.median_time_secs),
+            point
+                .peak_rss_bytes
+                .map_or_else(|| String::from("n/a"), format_bytes)
+        );
+        points.push(point);
+    }
+
+    if points.is_empty() {
+        return Err("no size was measured".into());
+    }
+    let sizes: Vec<f64> = points.iter().map(|point| point.size as f64).collect();
+    let times: Vec<f64> = points.iter().map(|point| point.median_time_secs).collect();
+    let memory: Option<Vec<f64>> = points
+        .iter()
+        .map(|point| point.peak_rss_bytes.map(|bytes| bytes as f64))
+        .collect();
+    // Leave memory out of the fit unless it was measured at every size.
+    let memory = memory.unwrap_or_default();
+    let report = ScaleReport {
+        challenge: challenge.name().to_string(),
+        executable: args.executable.display().to_string(),
+        param: param.name.to_string(),
+        bits: args.bits,
+        fixed_params: options
+            .resolved_params(spec)
+            .into_iter()
+            .filter(|(name, _)| name != param.name)
+            .collect(),
+        seed: options.seed,
+        runs: args.runs,
+        recorded_at: unix_time(),
+        time_power_fit: fit_power_law(&sizes, &times),
+        time_class_fit: best_fit(&sizes, &times),
+        memory_power_fit: fit_power_law(&sizes, &memory),
+        memory_class_fit: best_fit(&sizes, &memory),
+        points,
+    };
+    println!();
+    print_fit("Time", report.time_power_fit, report.time_class_fit);
+    if memory.is_empty() {
+        println!("Memory: not fitted, as the peak could not be measured at every size");
+    } else {
+        print_fit("Memory", report.memory_power_fit, report.memory_class_fit);
+    }
+
+    let scale_dir = create_results_dir(&format!(
+        "{}-scale-{}",
+        challenge.name(),
+        report.recorded_at
+    ))?;
+    report.save(&scale_dir)?;
+    println!("Curve saved to {}", scale_dir.display());
+    Ok(())
+}
+
+/// Sizes from `from` growing by `factor` up to `to`, which is always included.
+fn geometric_series(from: u128, to: u128, factor: f64) -> Vec<u128> {
+    let mut sizes = vec![from];
+    let mut size = from;
+    while size < to {
+        // Round up, and always grow by at least one, so small sizes make progress.
+        size = ((size as f64 * factor).ceil() as u128)
+            .max(size + 1)
+            .min(to);
+        sizes.push(size);
+    }
+    sizes
+}
+
+/// Prints the fitted complexity of one measurement, e.g. time.
+fn print_fit(what: &str, power: Option<PowerFit>, class: Option<ClassFit>) {
+    match class {
+        Some(class) => println!(
+            "\x1b[33m{what}: best fit {} (relative error {:.1}%)\x1b[0m",
+            class.complexity,
+            class.relative_error * 100.0
+        ),
+        None => println!("{what}: not enough sizes to fit a complexity class"),
+    }
+    if let Some(power) = power {
+        println!(
+            "{what}: grows like n^{:.2} (R² {:.3})",
+            power.exponent, power.r_squared
+        );
+    }
+}
+
+fn report(session: Option<PathBuf>) -> Result<(), Box<dyn Error>> {
+    let session_dir = session_dir_or_latest(session)?;
+    let summary_path = session_dir.join(SUMMARY_FILE);
+    let summary = fs::read_to_string(&summary_path)
+        .map_err(|err| format!("failed to read {}: {err}", summary_path.display()))?;
+    println!("Session {}", session_dir.display());
+    print!("{summary}");
+    Ok(())
+}
+
+/// Rebuilds the leaderboard of a session from its report, and saves it again.
+fn leaderboard(session: Option<PathBuf>) -> Result<(), Box<dyn Error>> {
+    let session_dir = session_dir_or_latest(session)?;
+    let table = SessionReport::load(&session_dir)?.leaderboard_table();
+    fs::write(session_dir.join(LEADERBOARD_FILE), &table)?;
+    println!("Session {}", session_dir.display());
+    print!("{table}");
+    Ok(())
+}
+
+/// Compares the latest version of `executable`, or of every submission, with its
+/// previous versions benchmarked on this machine with the same parameters. Fails if
+/// any got significantly slower.
+fn compare(
+    challenge: &dyn Challenge,
+    executable: Option<&str>,
+    params: &[(String, u128)],
+) -> Result<(), Box<dyn Error>> {
+    let mut options = InputOptions::default();
+    for (name, value) in params {
+        options.set_param(challenge.spec(), name, *value)?;
+    }
+    let params = options.resolved_params(challenge.spec());
+    let machine = Machine::current();
+    let records: Vec<HistoryRecord> = load_history()?
+        .into_iter()
+        .filter(|record| {
+            record.challenge == challenge.name()
+                && record.params == params
+                && record.machine == machine.fingerprint
+        })
+        .collect();
+    let mut executables: Vec<&str> = match executable {
+        Some(executable) => vec![executable],
+        None => records
+            .iter()
+            .map(|record| record.executable.as_str())
+            .collect(),
+    };
+    executables.sort_unstable();
+    executables.dedup();
+    if executables.is_empty() {
+        println!(
+            "No history for {} with these parameters on this machine ({}).",
+            challenge.name(),
+            machine.description
+        );
+        return Ok(());
+    }
+
+    println!(
+        "Comparing versions on {} ({}) on {}",
+        challenge.name(),
+        params
+            .iter()
+            .map(|(name, value)| format!("{name}={}", format_param_value(*value)))
+            .collect::<Vec<String>>()
+            .join(", "),
+        machine.description
+    );
+    let now = unix_time();
+    let mut regressions = Vec::new();
+    for executable in executables {
+        let versions = versions(&records, executable);
+        let Some((latest, previous)) = versions.split_first() else {
+            println!("\n{executable}: no history");
+            continue;
+        };
+        println!(
+            "\n\x1b[33m{executable}\x1b[0m: latest version {} ({})",
+            &latest.sha256[..12],
+            describe_version(latest, now)
+        );
+        if previous.is_empty() {
+            println!("  No previous versions to compare with.");
+        }
+        for version in previous {
+            if latest.times.is_empty() || version.times.is_empty() {
+                println!(
+                    "  vs {} ({}): no accepted runs to compare",
+                    &version.sha256[..12],
+                    describe_version(version, now)
+                );
+                continue;
+            }
+            let ratio = median(&latest.times) / median(&version.times);
+            let change = match mann_whitney_u(&latest.times, &version.times) {
+                Some(test) if test.p_value < SIGNIFICANCE && ratio > 1.0 => {
+                    regressions.push(executable);
+                    format!(
+                        "\x1b[31m{ratio:.2}x slower, significant (p = {:.3})\x1b[0m",
+                        test.p_value
+                    )
+                }
+                Some(test) if test.p_value < SIGNIFICANCE => format!(
+                    "\x1b[32m{:.2}x faster, significant (p = {:.3})\x1b[0m",
+                    1.0 / ratio,
+                    test.p_value
+                ),
+                Some(test) => format!(
+                    "{ratio:.2}x the time, not significant (p = {:.3})",
+                    test.p_value
+                ),
+                None => String::from("no runs to compare"),
             };
-            (
-                reference_reverse(&input),
-                String::from("./EXE_FILES_HERE/REVERSE_STRING"),
-            )
+            println!(
+                "  vs {} ({}): {change}",
+                &version.sha256[..12],
+                describe_version(version, now)
+            );
+        }
+    }
+    regressions.dedup();
+    if regressions.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("significant slowdown of {}", regressions.join(", ")).into())
+    }
+}
+
+/// E.g. `2 sessions, 20 accepted runs, median 1.2000ms, last run 3h ago`.
+fn describe_version(version: &Version, now: u64) -> String {
+    let median = if version.times.is_empty() {
+        String::from("n/a")
+    } else {
+        format_time(median(&version.times))
+    };
+    let mut description = format!(
+        "{} sessions, {} accepted runs, median {median}, last run {}",
+        version.sessions,
+        version.times.len(),
+        format_age(now.saturating_sub(version.last_seen))
+    );
+    if !version.all_accepted {
+        description.push_str(", \x1b[31mnot always correct\x1b[0m");
+    }
+    description
+}
+
+/// A coarse age, e.g. `45s ago` or `3d ago`.
+fn format_age(seconds: u64) -> String {
+    match seconds {
+        0..60 => format!("{seconds}s ago"),
+        60..3_600 => format!("{}m ago", seconds / 60),
+        3_600..86_400 => format!("{}h ago", seconds / 3_600),
+        _ => format!("{}d ago", seconds / 86_400),
+    }
+}
+
+/// Renders the HTML report of a session from its `report.json`.
+fn html(session: Option<PathBuf>) -> Result<(), Box<dyn Error>> {
+    let session_dir = session_dir_or_latest(session)?;
+    let report = SessionReport::load(&session_dir)?;
+    let html_path = session_dir.join(HTML_FILE);
+    fs::write(&html_path, render_html(&report, &scale_reports(&report)))?;
+    println!("HTML report saved to {}", html_path.display());
+    Ok(())
+}
+
+/// The latest `scale` curve of every executable in `report` for each series it was
+/// scaled over, in leaderboard order.
+fn scale_reports(report: &SessionReport) -> Vec<ScaleReport> {
+    let prefix = format!("{}-scale-", report.challenge);
+    let mut latest: Vec<ScaleReport> = Vec::new();
+    let Ok(entries) = fs::read_dir(RESULTS_DIR) else {
+        return latest;
+    };
+    for entry in entries.flatten() {
+        if !entry.file_name().to_string_lossy().starts_with(&prefix) {
+            continue;
+        }
+        let Ok(scale) = ScaleReport::load(&entry.path()) else {
+            continue;
+        };
+        if scale.challenge != report.challenge {
+            continue;
+        }
+        match latest.iter_mut().find(|other| {
+            other.executable_name() == scale.executable_name() && other.same_series(&scale)
+        }) {
+            Some(other) if other.recorded_at < scale.recorded_at => *other = scale,
+            Some(_) => {}
+            None => latest.push(scale),
         }
+    }
+    report
+        .leaderboard()
+        .iter()
+        .flat_map(|standing| {
+            latest
+                .iter()
+                .filter(|scale| scale.executable_name() == standing.name)
+                .cloned()
+                .collect::<Vec<ScaleReport>>()
+        })
+        .collect()
+}
+
+/// `session`, or the most recent session if it is `None`.
+fn session_dir_or_latest(session: Option<PathBuf>) -> Result<PathBuf, Box<dyn Error>> {
+    match session {
+        Some(dir) => Ok(dir),
+        None => Ok(latest_session_dir()?.ok_or("no benchmark sessions found in ./results")?),
+    }
+}
 
-        "reverse_large" => {
-            input = match fs::read_to_string("./input/wordlist_20M.txt") {
-                Ok(s) => s,
-                Err(_) => wordlist(20_000_000).expect("Failed to write custom wordlist."),
+/// Median in-process time of the challenge's reference implementation on the first
+/// run's input.
+fn time_reference(inputs: &mut Inputs) -> Result<f64, Box<dyn Error>> {
+    let challenge = inputs.challenge;
+    let options = inputs.options.clone();
+    let (_, input, _) = inputs.for_run(1)?;
+    let times: Vec<f64> = (0..REFERENCE_RUNS)
+        .map(|_| {
+            let start = Instant::now();
+            challenge.expected_output(input, &options);
+            start.elapsed().as_secs_f64()
+        })
+        .collect();
+    Ok(median(&times))
+}
+
+/// Recomputes the cached reference outputs of `challenge`, or of every challenge.
+fn check_references(challenge: Option<&'static dyn Challenge>) -> Result<(), Box<dyn Error>> {
+    let challenges = match challenge {
+        Some(challenge) => vec![challenge],
+        None => CHALLENGES.to_vec(),
+    };
+    let mut checked = 0;
+    let mut mismatches = 0;
+    for challenge in challenges {
+        for check in verify_references(challenge)? {
+            let manifest = &check.manifest;
+            let status = match &check.result {
+                ReferenceResult::Match => String::from("\x1b[32mOK\x1b[0m"),
+                ReferenceResult::Mismatch { recomputed_sha256 } => {
+                    mismatches += 1;
+                    format!(
+                        "\x1b[31mMISMATCH\x1b[0m (cached {}, now {})",
+                        &manifest.expected_output_sha256[..12],
+                        &recomputed_sha256[..12]
+                    )
+                }
+                ReferenceResult::Outdated => format!(
+                    "skipped (computed by version {}, now {})",
+                    manifest.version,
+                    challenge.spec().version
+                ),
+                ReferenceResult::MissingInput => String::from("skipped (input no longer cached)"),
             };
-            (
-                reference_reverse(&input),
-                String::from("./EXE_FILES_HERE/REVERSE_STRING"),
-            )
-        }
-        "gcd" => {
-            input = generate_gcd_numbers(20_000, 1_000_000).expect("Failed to generate numbers.");
-            (
-                reference_gcd(&input),
-                String::from("./EXE_FILES_HERE/GREATEST_COMMON_DIVISOR"),
-            )
-        }
-        "gcd_hard" => {
-            input = generate_gcd_numbers_large_capacity(200_000) // u128::MAX
-                .expect("Failed to generate numbers.");
-            (
-                reference_gcd_large_capacity(&input),
-                String::from("./EXE_FILES_HERE/GREATEST_COMMON_DIVISOR"),
-            )
-        }
-        "obelisk" => {
-            input = gen_obelisks();
-            let obelisks: Vec<u128> = input
-                .split(" ")
-                .map(|obelisk| obelisk.parse::<u128>().unwrap())
-                .collect();
-            (
-                reference_obelisk_count(&obelisks, 25).to_string(),
-                String::from("./EXE_FILES_HERE/OBELISK_EASY"),
-            )
-        }
-        "obelisk_hard" => {
-            input = gen_obelisks();
-            let obelisks: Vec<u128> = input
-                .split(" ")
-                .map(|obelisk| obelisk.parse::<u128>().unwrap())
+            if matches!(
+                check.result,
+                ReferenceResult::Match | ReferenceResult::Mismatch { .. }
+            ) {
+                checked += 1;
+            }
+            let params: Vec<String> = manifest
+                .params
+                .iter()
+                .map(|(name, value)| format!("{name}={value}"))
                 .collect();
-            (
-                reference_obelisk_count(&obelisks, 100).to_string(),
-                String::from("./EXE_FILES_HERE/OBELISK_HARD"),
-            )
-        }
-        _ => {
-            eprintln!(
-                "Unknown challenge: {}. Use [reverse|reverse_large|gcd|gcd_hard|obelisk|obelisk_hard].",
-                challenge
+            println!(
+                "{:<14} input {} ({}) {}",
+                manifest.challenge,
+                &manifest.input_sha256[..12],
+                params.join(", "),
+                status
             );
-            std::process::exit(1);
         }
+    }
+    if checked == 0 {
+        println!("No cached reference outputs to check; run a challenge first.");
+    }
+    if mismatches > 0 {
+        return Err(format!("{mismatches} of {checked} reference outputs changed").into());
+    }
+    Ok(())
+}
+
+/// Prints the parameter table, or replaces the one between the markers in README.md.
+fn readme(write: bool) -> Result<(), Box<dyn Error>> {
+    let table = params_markdown();
+    if !write {
+        print!("{table}");
+        return Ok(());
+    }
+    let readme = fs::read_to_string(README_FILE)
+        .map_err(|err| format!("failed to read {README_FILE}: {err}"))?;
+    let (Some(start), Some(end)) = (readme.find(PARAMS_START), readme.find(PARAMS_END)) else {
+        return Err(format!("{README_FILE} has no {PARAMS_START} ... {PARAMS_END} markers").into());
     };
+    let start = start + PARAMS_START.len();
+    let updated = format!("{}\n{table}{}", &readme[..start], &readme[end..]);
+    fs::write(README_FILE, updated)?;
+    println!("Updated the parameter table in {README_FILE}");
+    Ok(())
+}
 
-    // Iterate over executables in the given directory.
-    let entries = fs::read_dir(executables_dir).expect("Failed to read executables directory");
+/// A fresh, empty session directory for `challenge`.
+fn new_session_dir(challenge: &dyn Challenge) -> Result<PathBuf, Box<dyn Error>> {
+    create_results_dir(&format!("{}-{}", challenge.name(), unix_time()))
+}
+
+/// Creates the directory `stem` in the results directory, or `stem-2`, `stem-3` and
+/// so on if another session started in the same second already took the name.
+fn create_results_dir(stem: &str) -> Result<PathBuf, Box<dyn Error>> {
+    fs::create_dir_all(RESULTS_DIR)?;
+    let mut attempt = 1;
+    loop {
+        let name = match attempt {
+            1 => stem.to_string(),
+            _ => format!("{stem}-{attempt}"),
+        };
+        let dir = Path::new(RESULTS_DIR).join(name);
+        match fs::create_dir(&dir) {
+            Ok(()) => return Ok(dir),
+            Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => attempt += 1,
+            Err(err) => return Err(format!("failed to create {}: {err}", dir.display()).into()),
+        }
+    }
+}
+
+/// Seconds since the Unix epoch.
+fn unix_time() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// The most recently modified session directory that has a summary.
+fn latest_session_dir() -> Result<Option<PathBuf>, Box<dyn Error>> {
+    let Ok(entries) = fs::read_dir(RESULTS_DIR) else {
+        return Ok(None);
+    };
+    let mut latest: Option<(SystemTime, PathBuf)> = None;
     for entry in entries {
-        let entry = entry.expect("Error reading a directory entry");
-        let name = entry.file_name();
-        if name == "PUT YOUR .EXE FILE IN THIS FOLDER.md" {
+        let path = entry?.path();
+        let Ok(metadata) = fs::metadata(path.join(SUMMARY_FILE)) else {
             continue;
+        };
+        let modified = metadata.modified()?;
+        if latest.as_ref().is_none_or(|(time, _)| modified > *time) {
+            latest = Some((modified, path));
         }
-        let path = entry.path();
+    }
+    Ok(latest.map(|(_, path)| path))
+}
 
-        if path.is_file() {
-            println!("Benchmarking executable: {:?}", path);
-            let mut times = Vec::new();
-            let mut all_passed = true;
-
-            // Run the executable num_runs times.
-            for run in 1..=num_runs {
-                let start = Instant::now();
-                let output = run_executable(&path, &input);
-                let duration = start.elapsed();
-                let duration_secs = duration.as_secs_f64();
-                times.push(duration_secs);
-
-                let formatted_time = format_time(duration_secs);
-                let pass = output.trim() == expected_output.trim();
-                if !pass {
-                    all_passed = false;
-                    if run == 1 && (matches!(challenge.as_str(), "obelisk" | "obelisk_hard")) {
-                        if output.trim() > expected_output.trim() {
-                            println!("INFO: The provided answer was too high.");
-                        } else {
-                            println!("INFO: The provided answer was too low.")
-                        }
-                    }
-                }
-                // Print run result: green if pass, red if fail.
-                if pass {
-                    // Green: \x1b[32m, Reset: \x1b[0m
-                    println!("\x1b[32mRun {}: {}\x1b[0m", run, formatted_time);
+/// Generates challenge inputs and their expected outputs, keeping the latest pair
+/// so runs that share a seed do not regenerate it.
+struct Inputs<'a> {
+    challenge: &'a dyn Challenge,
+    options: InputOptions,
+    /// Whether every run gets its own seed.
+    per_run: bool,
+    /// Whether inputs are loaded from and stored in the input cache.
+    cached: bool,
+    /// Whether output is judged against a digest while it streams in.
+    streaming: bool,
+    /// Seed, input and expected output of the last generated input.
+    current: Option<(u64, String, Expected)>,
+}
+
+impl<'a> Inputs<'a> {
+    fn new(
+        challenge: &'a dyn Challenge,
+        options: InputOptions,
+        per_run: bool,
+        cached: bool,
+        streaming: bool,
+    ) -> Self {
+        Inputs {
+            challenge,
+            options,
+            per_run,
+            cached,
+            streaming,
+            current: None,
+        }
+    }
+
+    /// The seed, input and expected output for run `run`.
+    fn for_run(&mut self, run: u32) -> Result<(u64, &str, &Expected), Box<dyn Error>> {
+        let seed = if self.per_run {
+            seed_for_run(self.options.seed, run)
+        } else {
+            self.options.seed
+        };
+        if self
+            .current
+            .as_ref()
+            .is_none_or(|(last, _, _)| *last != seed)
+        {
+            let mut options = self.options.clone();
+            options.seed = seed;
+            let (input, expected) = if self.cached {
+                let cached = load_or_generate(self.challenge, &options, self.streaming)?;
+                print_cache_status("input", &cached.input_status);
+                print_cache_status("reference output", &cached.reference_status);
+                (cached.input, cached.expected)
+            } else {
+                let input = self.challenge.generate_input(&options)?;
+                let expected_output = self.challenge.expected_output(&input, &options);
+                let expected = if self.streaming {
+                    Expected::Digest(ChunkedDigest::of(&expected_output))
                 } else {
-                    // Red: \x1b[31m, Reset: \x1b[0m
-                    println!("\x1b[31mRun {}: {}\x1b[0m", run, formatted_time);
-                    println!("❌ Output incorrect on run {}.", run);
-                }
+                    Expected::Output(expected_output)
+                };
+                (input, expected)
+            };
+            self.current = Some((seed, input, expected));
+        }
+        let (seed, input, expected) = self.current.as_ref().expect("generated above");
+        Ok((*seed, input, expected))
+    }
+}
+
+/// Says whether `what` came from the input cache.
+fn print_cache_status(what: &str, status: &CacheStatus) {
+    match status {
+        CacheStatus::Hit => println!("Loaded {what} from {CACHE_DIR}"),
+        CacheStatus::Miss => println!("Generated {what} and cached it in {CACHE_DIR}"),
+        CacheStatus::Stale(reason) => {
+            println!("Cached {what} was stale ({reason}); regenerated it")
+        }
+    }
+}
+
+/// What a benchmark of one executable produced.
+struct ExecutableResult {
+    /// One plain-text summary line for the session summary.
+    summary: String,
+    /// Every run and the statistics, for the session report.
+    report: ExecutableReport,
+}
+
+/// How many times to run each executable.
+struct RunPlan {
+    /// Runs before the measured ones that are judged but left out of the statistics.
+    warmup: u32,
+    /// Measured runs, or the most measured runs in adaptive mode.
+    max_runs: u32,
+    /// Adaptive mode: stop once the median's 95% confidence interval is narrower than
+    /// this fraction of the median.
+    target_ci: Option<f64>,
+    /// Adaptive mode: stop once the measured runs have taken this long.
+    time_budget: Option<Duration>,
+}
+
+impl RunPlan {
+    fn fixed(runs: u32) -> Self {
+        RunPlan {
+            warmup: 0,
+            max_runs: runs,
+            target_ci: None,
+            time_budget: None,
+        }
+    }
+
+    fn is_adaptive(&self) -> bool {
+        self.target_ci.is_some() || self.time_budget.is_some()
+    }
+
+    /// Whether the `runs` measured runs so far, which started at `started` and whose
+    /// accepted ones took `times`, are enough.
+    fn is_done(&self, runs: u32, times: &[f64], started: Instant) -> bool {
+        if runs >= self.max_runs {
+            return true;
+        }
+        if let Some(budget) = self.time_budget
+            && runs > 0
+            && started.elapsed() >= budget
+        {
+            return true;
+        }
+        match self.target_ci {
+            Some(target) if times.len() >= ADAPTIVE_MIN_RUNS => {
+                let step = (times.len() / CI_RECHECK_DIVISOR).max(1);
+                times.len().is_multiple_of(step)
+                    && median_ci(times).width() <= target * median(times)
             }
+            _ => false,
+        }
+    }
+}
 
-            // Compute summary statistics.
-            let sum: f64 = times.iter().sum();
-            let count = times.len() as f64;
-            let average = sum / count;
-            let min = times.iter().cloned().fold(f64::INFINITY, f64::min);
-            let max = times.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
-            let variance = times.iter().map(|&t| (t - average).powi(2)).sum::<f64>() / count;
-            let std_dev = variance.sqrt();
+impl fmt::Display for RunPlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_adaptive() {
+            write!(f, "adaptive")?;
+            if let Some(target) = self.target_ci {
+                write!(f, ", median CI within {}%", target * 100.0)?;
+            }
+            if let Some(budget) = self.time_budget {
+                write!(f, ", budget {}", format_time(budget.as_secs_f64()))?;
+            }
+            write!(f, ", at most {} runs", self.max_runs)?;
+        } else {
+            write!(f, "{} runs", self.max_runs)?;
+        }
+        if self.warmup > 0 {
+            write!(f, " + {} warmup", self.warmup)?;
+        }
+        Ok(())
+    }
+}
 
-            // Print summary on one line with colors.
-            println!(
-                "\n\x1b[33mSummary for {:?}: \n\x1b[36mAvg: {}\x1b[0m | \x1b[32mMin: {}\x1b[0m | \x1b[31mMax: {}\x1b[0m | \x1b[33mStd Dev: {}\x1b[0m",
-                entry.file_name(),
-                format_time(average),
-                format_time(min),
-                format_time(max),
-                format_time(std_dev)
-            );
+/// Runs `path` as `plan` says against the challenge input, printing each run and a
+/// summary.
+fn benchmark_executable(
+    path: &Path,
+    inputs: &mut Inputs,
+    limits: &Limits,
+    plan: &RunPlan,
+    session_dir: &Path,
+) -> Result<ExecutableResult, Box<dyn Error>> {
+    let challenge = inputs.challenge;
+    let per_run = inputs.per_run;
+    let name = path.file_name().unwrap_or(path.as_os_str());
+    let checker = challenge.checker();
+    println!("Benchmarking executable: {:?}", path);
+    // Only accepted runs are timed: a crash or a wrong answer says nothing about speed.
+    let mut times = Vec::new();
+    let mut timed_runs = Vec::new();
+    let mut peak_rss: Option<u64> = None;
+    let mut usages = Vec::new();
+    let mut records = Vec::new();
+    let mut explained_wrong_answer = false;
 
-            // Final pass/fail message.
-            if all_passed {
-                println!("\x1b[32m✅ Output correct on all runs.\x1b[0m");
+    // Warm up first, then make measured runs until the plan is satisfied.
+    let mut attempt = 0;
+    let mut run = 0;
+    let mut started = Instant::now();
+    loop {
+        let warmup = attempt < plan.warmup;
+        attempt += 1;
+        if !warmup {
+            if run == 0 {
+                started = Instant::now();
+            } else if plan.is_done(run, &times, started) {
+                break;
+            }
+            run += 1;
+        }
+        // Warmups run on the first measured run's input.
+        let (seed, input, expected) = inputs.for_run(run.max(1))?;
+        let (outcome, verdict, mismatch) = judge(path, input, expected, &checker, limits);
+        let duration_secs = outcome.elapsed.as_secs_f64();
+
+        let mut formatted_time = format_time(duration_secs);
+        if let Some(usage) = outcome.usage {
+            formatted_time.push_str(&format!(
+                " ({}, user {}, sys {}, CPU/wall {:.2}, ctx switches {}/{})",
+                usage
+                    .peak_rss_bytes
+                    .map_or_else(|| String::from("n/a"), format_bytes),
+                format_time(usage.user_cpu.as_secs_f64()),
+                format_time(usage.system_cpu.as_secs_f64()),
+                usage.cpu_wall_ratio(outcome.elapsed),
+                usage.voluntary_context_switches,
+                usage.involuntary_context_switches
+            ));
+            if !warmup {
+                peak_rss = peak_rss.max(usage.peak_rss_bytes);
+                usages.push((usage, outcome.elapsed));
+            }
+        }
+        // Point out where the output went wrong, once per executable.
+        if matches!(verdict, Verdict::WrongAnswer | Verdict::NotANumber) && !explained_wrong_answer
+        {
+            explained_wrong_answer = true;
+            let explanation = match (&mismatch, expected) {
+                (Some(mismatch), _) => mismatch.to_string(),
+                (None, Expected::Output(expected_output)) => {
+                    challenge.explain_wrong_answer(expected_output, &outcome.stdout)
+                }
+                (None, Expected::Digest(_)) => String::from("The output differs."),
+            };
+            println!("INFO: {explanation}");
+        }
+        // Print run result: green if accepted, red otherwise.
+        let mut label = if warmup {
+            format!("Warmup run {attempt}")
+        } else {
+            format!("Run {run}")
+        };
+        if per_run {
+            label.push_str(&format!(" (seed {seed})"));
+        }
+        if verdict.is_accepted() {
+            // Green: \x1b[32m, Reset: \x1b[0m
+            println!("\x1b[32m{}: {}\x1b[0m", label, formatted_time);
+        } else {
+            // Red: \x1b[31m, Reset: \x1b[0m
+            println!("\x1b[31m{}: {}\x1b[0m", label, formatted_time);
+            println!("❌ {} on {}.", verdict, label.to_lowercase());
+            if !outcome.stderr.trim().is_empty() {
+                // Dim: \x1b[2m, Reset: \x1b[0m
+                println!("Last lines of stderr:");
+                for line in tail_lines(&outcome.stderr, STDERR_TAIL_LINES) {
+                    println!("\x1b[2m  {}\x1b[0m", line);
+                }
+            }
+        }
+
+        // Keep the full stderr of every run so authors can debug without rerunning.
+        if !outcome.stderr.is_empty() {
+            let log_name = if warmup {
+                format!("warmup{attempt}")
             } else {
-                println!("\x1b[31m❌ Some runs produced incorrect output.\x1b[0m");
+                format!("run{run}")
+            };
+            match save_stderr(session_dir, name, &log_name, &outcome) {
+                Ok(log_path) => println!("Stderr saved to {}", log_path.display()),
+                Err(err) => eprintln!("Warning: failed to save stderr: {err}"),
             }
-            println!("----------------------------------");
         }
+
+        // A binary that cannot be started will not start on the next run either,
+        // and one that hit a limit will hit it again on the same input.
+        let spawn_failed = matches!(verdict, Verdict::SpawnFailed(_));
+        let hit_limit = matches!(
+            verdict,
+            Verdict::TimeLimitExceeded | Verdict::MemoryLimitExceeded
+        );
+        if verdict.is_accepted() && !warmup {
+            times.push(duration_secs);
+            timed_runs.push(run);
+        }
+        records.push(RunRecord {
+            run: if warmup { attempt } else { run },
+            warmup,
+            seed,
+            verdict: verdict.short().to_string(),
+            verdict_detail: verdict.to_string(),
+            accepted: verdict.is_accepted(),
+            wall_time_secs: duration_secs,
+            peak_rss_bytes: outcome.usage.and_then(|usage| usage.peak_rss_bytes),
+            user_cpu_secs: outcome.usage.map(|usage| usage.user_cpu.as_secs_f64()),
+            system_cpu_secs: outcome.usage.map(|usage| usage.system_cpu.as_secs_f64()),
+            voluntary_context_switches: outcome.usage.map(|usage| usage.voluntary_context_switches),
+            involuntary_context_switches: outcome
+                .usage
+                .map(|usage| usage.involuntary_context_switches),
+        });
+        if spawn_failed || hit_limit {
+            break;
+        }
+    }
+
+    let mut report = ExecutableReport {
+        name: name.to_string_lossy().into_owned(),
+        path: path.display().to_string(),
+        sha256: fs::read(path).ok().map(|bytes| sha256_hex(&bytes)),
+        all_accepted: false,
+        runs: records,
+        wall_time_secs: None,
+        threading: None,
+    };
+    let Some(stats) = summarize(&times) else {
+        let reason = if report.runs.iter().any(|run| !run.warmup) {
+            format!("had no accepted runs: {}", report.verdict_counts())
+        } else {
+            String::from("could not be run")
+        };
+        println!("\n\x1b[31m❌ {:?} {reason}.\x1b[0m", name);
+        println!("----------------------------------");
+        return Ok(ExecutableResult {
+            summary: format!("{}: {reason}", name.to_string_lossy()),
+            report,
+        });
+    };
+
+    let peak_rss = match peak_rss {
+        Some(bytes) => format_bytes(bytes),
+        None => String::from("n/a"),
+    };
+
+    // Say how many runs the statistics rest on, which varies in adaptive mode.
+    if plan.is_adaptive() || plan.warmup > 0 {
+        println!(
+            "\nMeasured {} runs in {} after {} warmup runs.",
+            run,
+            format_time(started.elapsed().as_secs_f64()),
+            plan.warmup
+        );
+    }
+    if stats.count < run as usize {
+        println!(
+            "Timings cover only the {} accepted runs; failed runs are counted below.",
+            stats.count
+        );
+    }
+
+    // Print summary on one line with colors.
+    println!(
+        "\n\x1b[33mSummary for {:?}: \n\x1b[36mAvg: {}\x1b[0m | \x1b[32mMin: {}\x1b[0m | \x1b[31mMax: {}\x1b[0m | \x1b[33mStd Dev: {}\x1b[0m | \x1b[35mPeak RSS: {}\x1b[0m",
+        name,
+        format_time(stats.mean),
+        format_time(stats.min),
+        format_time(stats.max),
+        format_time(stats.std_dev),
+        peak_rss
+    );
+    println!(
+        "\x1b[36mMedian: {}\x1b[0m | p90: {} | p99: {} | MAD: {} | 95% CI mean: {} | 95% CI median: {}",
+        format_time(stats.median),
+        format_time(stats.p90),
+        format_time(stats.p99),
+        format_time(stats.mad),
+        format_interval(stats.mean_ci),
+        format_interval(stats.median_ci)
+    );
+    if !stats.outliers.is_empty() {
+        let runs: Vec<String> = stats
+            .outliers
+            .iter()
+            .map(|&i| format!("run {} ({})", timed_runs[i], format_time(times[i])))
+            .collect();
+        println!(
+            "\x1b[33mOutliers (outside 1.5 IQR of the quartiles): {}\x1b[0m",
+            runs.join(", ")
+        );
+    }
+
+    // CPU accounting tells single- and multi-threaded submissions apart.
+    if !usages.is_empty() {
+        let count = usages.len() as f64;
+        let user = usages
+            .iter()
+            .map(|(u, _)| u.user_cpu.as_secs_f64())
+            .sum::<f64>();
+        let system = usages
+            .iter()
+            .map(|(u, _)| u.system_cpu.as_secs_f64())
+            .sum::<f64>();
+        let cpu_wall_ratio = usages
+            .iter()
+            .map(|(u, elapsed)| u.cpu_wall_ratio(*elapsed))
+            .sum::<f64>()
+            / count;
+        let threading = Threading::classify(
+            usages.iter().map(|(u, _)| u.cpu_time()).sum(),
+            usages.iter().map(|(_, elapsed)| *elapsed).sum(),
+            usages.len() as u32,
+        );
+        report.threading = Some(threading);
+        println!(
+            "\x1b[36mAvg user CPU: {}\x1b[0m | \x1b[36mAvg sys CPU: {}\x1b[0m | \x1b[33mCPU/wall: {:.2} ({})\x1b[0m",
+            format_time(user / count),
+            format_time(system / count),
+            cpu_wall_ratio,
+            threading
+        );
+    }
+
+    // Final pass/fail message.
+    let all_accepted = report.runs.iter().all(|run| run.accepted);
+    if all_accepted {
+        println!("\x1b[32m✅ Output correct on all runs.\x1b[0m");
+    } else {
+        println!(
+            "\x1b[31m❌ Some runs failed: {}\x1b[0m",
+            report.verdict_counts()
+        );
+    }
+    println!("----------------------------------");
+
+    let summary = format!(
+        "{}: {} | Runs: {} | Avg: {} | Min: {} | Max: {} | Std Dev: {} | Median: {} | p90: {} | p99: {} | MAD: {} | 95% CI mean: {} | 95% CI median: {} | Outliers: {} | Peak RSS: {}",
+        name.to_string_lossy(),
+        report.verdict_counts(),
+        stats.count,
+        format_time(stats.mean),
+        format_time(stats.min),
+        format_time(stats.max),
+        format_time(stats.std_dev),
+        format_time(stats.median),
+        format_time(stats.p90),
+        format_time(stats.p99),
+        format_time(stats.mad),
+        format_interval(stats.mean_ci),
+        format_interval(stats.median_ci),
+        stats.outliers.len(),
+        peak_rss
+    );
+    report.all_accepted = all_accepted;
+    report.wall_time_secs = Some(stats);
+    Ok(ExecutableResult { summary, report })
+}
+
+/// Runs `path` once on `input` and judges its output against `expected`, streaming it
+/// if there is only a digest to compare with.
+fn judge(
+    path: &Path,
+    input: &str,
+    expected: &Expected,
+    checker: &Checker,
+    limits: &Limits,
+) -> (RunOutcome, Verdict, Option<ChunkMismatch>) {
+    match expected {
+        Expected::Output(expected_output) => {
+            let outcome = run_executable(path, input, limits);
+            let verdict = outcome.verdict(checker, input, expected_output);
+            (outcome, verdict, None)
+        }
+        Expected::Digest(digest) => {
+            let mut verifier = StreamingVerifier::new(digest);
+            let outcome = run_executable_streaming(path, input, limits, &mut verifier);
+            let mismatch = verifier.finish();
+            let verdict = outcome.streamed_verdict(mismatch.is_none());
+            (outcome, verdict, mismatch)
+        }
+    }
+}
+
+/// Formats a confidence interval of times, e.g. `[1.2000ms, 1.4000ms]`.
+fn format_interval(interval: Interval) -> String {
+    format!(
+        "[{}, {}]",
+        format_time(interval.low),
+        format_time(interval.high)
+    )
+}
+
+/// Returns the last `n` lines of `text`.
+fn tail_lines(text: &str, n: usize) -> Vec<&str> {
+    let lines: Vec<&str> = text.trim_end().lines().collect();
+    lines[lines.len().saturating_sub(n)..].to_vec()
+}
+
+/// Writes the captured stderr of one run to `<session_dir>/<executable>.<run>.stderr.txt`,
+/// where `run` is e.g. `run3` or `warmup1`.
+fn save_stderr(
+    session_dir: &Path,
+    executable: &OsStr,
+    run: &str,
+    outcome: &RunOutcome,
+) -> std::io::Result<PathBuf> {
+    fs::create_dir_all(session_dir)?;
+    let log_path = session_dir.join(format!(
+        "{}.{}.stderr.txt",
+        executable.to_string_lossy(),
+        run
+    ));
+    let mut contents = String::new();
+    if outcome.stderr_truncated {
+        contents.push_str(&format!(
+            "[earlier output truncated, last {STDERR_LIMIT} bytes kept]\n"
+        ));
     }
+    contents.push_str(&outcome.stderr);
+    fs::write(&log_path, contents)?;
+    Ok(log_path)
 }
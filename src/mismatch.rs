@@ -0,0 +1,341 @@
+use num_bigint::BigInt;
+use std::collections::VecDeque;
+use std::fmt;
+
+/// Where and how a submission's output first diverges from the expected output,
+/// compared token by token (tokens are separated by any whitespace).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch {
+    /// Zero-based index of the first differing token.
+    pub index: usize,
+    /// One-based line of the output on which the first differing token appears.
+    pub line: usize,
+    /// One-based column, in characters, at which the first differing token starts,
+    /// or just past the end of the output if it ended early.
+    pub column: usize,
+    /// The expected token, or `None` if the output has extra tokens.
+    pub expected: Option<String>,
+    /// The actual token, or `None` if the output ended early.
+    pub actual: Option<String>,
+    /// Tokens before the mismatch, which both sides agree on.
+    pub before: Vec<String>,
+    /// Expected tokens following the mismatch.
+    pub expected_after: Vec<String>,
+    /// Actual tokens following the mismatch.
+    pub actual_after: Vec<String>,
+    /// Number of positions where both sides have a token but they differ.
+    pub differing: usize,
+    /// Total number of expected tokens.
+    pub expected_count: usize,
+    /// Total number of tokens in the output.
+    pub actual_count: usize,
+}
+
+impl Mismatch {
+    /// Tokens the output is missing compared to the expected output.
+    pub fn missing(&self) -> usize {
+        self.expected_count.saturating_sub(self.actual_count)
+    }
+
+    /// Tokens the output has beyond the expected output.
+    pub fn extra(&self) -> usize {
+        self.actual_count.saturating_sub(self.expected_count)
+    }
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let show = |token: &Option<String>| match token {
+            Some(token) => format!("{token:?}"),
+            None => String::from("<end of output>"),
+        };
+        writeln!(
+            f,
+            "First difference at token {} (line {}, column {}): expected {}, got {}",
+            self.index + 1,
+            self.line,
+            self.column,
+            show(&self.expected),
+            show(&self.actual)
+        )?;
+        writeln!(
+            f,
+            "  expected: ... {} [{}] {} ...",
+            self.before.join(" "),
+            show(&self.expected),
+            self.expected_after.join(" ")
+        )?;
+        writeln!(
+            f,
+            "  actual:   ... {} [{}] {} ...",
+            self.before.join(" "),
+            show(&self.actual),
+            self.actual_after.join(" ")
+        )?;
+        write!(
+            f,
+            "{} of {} expected tokens differ, {} missing, {} extra ({} tokens in output)",
+            self.differing,
+            self.expected_count,
+            self.missing(),
+            self.extra(),
+            self.actual_count
+        )
+    }
+}
+
+/// Splits `text` on ASCII whitespace, as [`Checker::Tokens`](crate::Checker::Tokens)
+/// does, pairing each token with its one-based line and column.
+fn tokens_with_positions(text: &str) -> impl Iterator<Item = ((usize, usize), &str)> {
+    text.split('\n').enumerate().flat_map(|(line, content)| {
+        content.split_ascii_whitespace().map(move |token| {
+            let offset = token.as_ptr() as usize - content.as_ptr() as usize;
+            ((line + 1, column_after(&content[..offset])), token)
+        })
+    })
+}
+
+/// One-based column just past `text` on its last line, in characters.
+fn column_after(text: &str) -> usize {
+    text.chars().count() + 1
+}
+
+/// Compares `actual` against `expected` token by token and describes the first
+/// difference, with up to `context` tokens around it.
+///
+/// Walks both outputs once without building a diff, so it stays cheap for very
+/// large outputs. Returns `None` if the token sequences are identical.
+pub fn first_mismatch(expected: &str, actual: &str, context: usize) -> Option<Mismatch> {
+    let mut expected_tokens = expected.split_ascii_whitespace();
+    let mut actual_tokens = tokens_with_positions(actual);
+    let mut before: VecDeque<&str> = VecDeque::with_capacity(context + 1);
+    let mut index = 0;
+
+    // Find the first position where the outputs disagree.
+    let ((line, column), expected_token, actual_token) = loop {
+        match (expected_tokens.next(), actual_tokens.next()) {
+            (None, None) => return None,
+            (Some(e), Some((_, a))) if e == a => {
+                before.push_back(e);
+                if before.len() > context {
+                    before.pop_front();
+                }
+                index += 1;
+            }
+            (e, Some((position, a))) => break (position, e, Some(a)),
+            (e, None) => {
+                let text = actual.trim_end();
+                let last_line = text.rsplit('\n').next().unwrap_or_default();
+                let position = (text.split('\n').count(), column_after(last_line));
+                break (position, e, None);
+            }
+        }
+    };
+
+    let mut mismatch = Mismatch {
+        index,
+        line,
+        column,
+        expected: expected_token.map(str::to_string),
+        actual: actual_token.map(str::to_string),
+        before: before.into_iter().map(str::to_string).collect(),
+        expected_after: Vec::new(),
+        actual_after: Vec::new(),
+        differing: usize::from(expected_token.is_some() && actual_token.is_some()),
+        expected_count: index + usize::from(expected_token.is_some()),
+        actual_count: index + usize::from(actual_token.is_some()),
+    };
+
+    // Keep walking to gather trailing context and totals.
+    loop {
+        let e = expected_tokens.next();
+        let a = actual_tokens.next().map(|(_, token)| token);
+        if e.is_none() && a.is_none() {
+            break;
+        }
+        if let Some(e) = e {
+            mismatch.expected_count += 1;
+            if mismatch.expected_after.len() < context {
+                mismatch.expected_after.push(e.to_string());
+            }
+        }
+        if let Some(a) = a {
+            mismatch.actual_count += 1;
+            if mismatch.actual_after.len() < context {
+                mismatch.actual_after.push(a.to_string());
+            }
+        }
+        if matches!((e, a), (Some(e), Some(a)) if e != a) {
+            mismatch.differing += 1;
+        }
+    }
+    Some(mismatch)
+}
+
+/// How a single-integer answer relates to the expected integer.
+#[derive(Debug, Clone, PartialEq)]
+pub enum IntegerAnswer {
+    /// The output is not an integer at all.
+    NotANumber,
+    /// The output is an integer; `difference` is actual minus expected and
+    /// `ratio` is actual divided by expected.
+    Number { difference: BigInt, ratio: f64 },
+}
+
+/// Bits kept of each side of an integer answer's ratio, well within the range of f64.
+const RATIO_BITS: u64 = 1000;
+
+/// Compares two integer answers of any size.
+///
+/// Returns `None` if `expected` itself is not an integer.
+pub fn compare_integer_answer(expected: &str, actual: &str) -> Option<IntegerAnswer> {
+    let expected_value: BigInt = expected.trim().parse().ok()?;
+    let Ok(actual_value) = actual.trim().parse::<BigInt>() else {
+        return Some(IntegerAnswer::NotANumber);
+    };
+    // Decimal strings of any length parse to the nearest f64, which is plenty for a
+    // ratio once both sides are scaled down to stay below f64::MAX.
+    let shift = expected_value
+        .bits()
+        .max(actual_value.bits())
+        .saturating_sub(RATIO_BITS);
+    let as_f64 = |value: &BigInt| {
+        (value >> shift)
+            .to_string()
+            .parse::<f64>()
+            .unwrap_or(f64::NAN)
+    };
+    Some(IntegerAnswer::Number {
+        ratio: as_f64(&actual_value) / as_f64(&expected_value),
+        difference: actual_value - expected_value,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identical_tokens_have_no_mismatch() {
+        assert_eq!(first_mismatch("1 2\n3", "1  2 3\n", 3), None);
+        assert_eq!(first_mismatch("", " \n", 3), None);
+    }
+
+    #[test]
+    fn reports_position_line_and_column_of_the_first_difference() {
+        let mismatch = first_mismatch("a b c d", "a b\n  x d", 3).unwrap();
+        assert_eq!(mismatch.index, 2);
+        assert_eq!(mismatch.line, 2);
+        assert_eq!(mismatch.column, 3);
+        assert_eq!(mismatch.expected.as_deref(), Some("c"));
+        assert_eq!(mismatch.actual.as_deref(), Some("x"));
+        assert_eq!(mismatch.differing, 1);
+        assert!(mismatch.to_string().starts_with(
+            "First difference at token 3 (line 2, column 3): expected \"c\", got \"x\""
+        ));
+    }
+
+    #[test]
+    fn columns_count_characters() {
+        let mismatch = first_mismatch("å b", "å\tc", 3).unwrap();
+        assert_eq!((mismatch.line, mismatch.column), (1, 3));
+    }
+
+    #[test]
+    fn context_is_truncated_on_both_sides() {
+        let mismatch = first_mismatch("1 2 3 4 5 6 7 8 9", "1 2 3 4 x 6 7 y 9", 2).unwrap();
+        assert_eq!(mismatch.before, ["3", "4"]);
+        assert_eq!(mismatch.expected_after, ["6", "7"]);
+        assert_eq!(mismatch.actual_after, ["6", "7"]);
+        assert_eq!(mismatch.differing, 2);
+
+        let mismatch = first_mismatch("1 2", "x 2", 5).unwrap();
+        assert!(mismatch.before.is_empty());
+        assert_eq!(mismatch.expected_after, ["2"]);
+    }
+
+    #[test]
+    fn counts_missing_tokens() {
+        let mismatch = first_mismatch("1 2 3 4", "1\n2\n", 3).unwrap();
+        assert_eq!(mismatch.index, 2);
+        assert_eq!(mismatch.expected.as_deref(), Some("3"));
+        assert_eq!(mismatch.actual, None);
+        assert_eq!((mismatch.line, mismatch.column), (2, 2));
+        assert_eq!(mismatch.expected_after, ["4"]);
+        assert_eq!((mismatch.missing(), mismatch.extra()), (2, 0));
+        assert_eq!(mismatch.differing, 0);
+        assert!(mismatch.to_string().contains("got <end of output>"));
+
+        let mismatch = first_mismatch("1", "", 3).unwrap();
+        assert_eq!((mismatch.line, mismatch.column), (1, 1));
+    }
+
+    #[test]
+    fn counts_extra_tokens() {
+        let mismatch = first_mismatch("1 2", "1 2 3 4", 3).unwrap();
+        assert_eq!(mismatch.index, 2);
+        assert_eq!(mismatch.expected, None);
+        assert_eq!(mismatch.actual.as_deref(), Some("3"));
+        assert_eq!(mismatch.actual_after, ["4"]);
+        assert_eq!((mismatch.missing(), mismatch.extra()), (0, 2));
+        assert!(
+            mismatch.to_string().ends_with(
+                "0 of 2 expected tokens differ, 0 missing, 2 extra (4 tokens in output)"
+            )
+        );
+    }
+
+    fn number(answer: Option<IntegerAnswer>) -> (BigInt, f64) {
+        match answer {
+            Some(IntegerAnswer::Number { difference, ratio }) => (difference, ratio),
+            other => panic!("expected a number, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn integer_answer_too_high_or_too_low() {
+        assert_eq!(
+            number(compare_integer_answer("40", "50")),
+            (BigInt::from(10), 1.25)
+        );
+        assert_eq!(
+            number(compare_integer_answer("40\n", " 30 ")),
+            (BigInt::from(-10), 0.75)
+        );
+        assert_eq!(
+            number(compare_integer_answer("40", "+040")),
+            (BigInt::from(0), 1.0)
+        );
+    }
+
+    #[test]
+    fn integer_answer_ratio_of_huge_numbers() {
+        let expected = format!("1{}", "0".repeat(400));
+        let actual = format!("3{}", "0".repeat(400));
+        let (difference, ratio) = number(compare_integer_answer(&expected, &actual));
+        assert_eq!(
+            difference,
+            BigInt::from(2) * expected.parse::<BigInt>().unwrap()
+        );
+        assert_eq!(ratio, 3.0);
+        let (_, ratio) = number(compare_integer_answer("2", &actual));
+        assert_eq!(ratio, f64::INFINITY);
+    }
+
+    #[test]
+    fn integer_answer_not_a_number() {
+        assert_eq!(
+            compare_integer_answer("40", "forty"),
+            Some(IntegerAnswer::NotANumber)
+        );
+        assert_eq!(
+            compare_integer_answer("40", "4.0e1"),
+            Some(IntegerAnswer::NotANumber)
+        );
+        assert_eq!(
+            compare_integer_answer("40", ""),
+            Some(IntegerAnswer::NotANumber)
+        );
+        assert_eq!(compare_integer_answer("forty", "40"), None);
+    }
+}
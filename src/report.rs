@@ -0,0 +1,691 @@
+//! Machine-readable session reports.
+//!
+//! Every `run` session writes a [`SessionReport`] to [`REPORT_FILE`] as JSON, plus two
+//! CSV views of it for spreadsheets: [`RUNS_CSV`] with one row per run and
+//! [`SUMMARY_CSV`] with one row per executable. The ranking of the executables is
+//! kept in [`LEADERBOARD_FILE`], and can be rebuilt from the report at any time.
+//!
+//! The `scale` command writes a [`ScaleReport`] to [`SCALE_JSON`] and its curve to
+//! [`SCALE_CSV`] in a directory of its own.
+
+use crate::challenges::format_param_value;
+use crate::complexity::{ClassFit, PowerFit};
+use crate::format_time;
+use crate::runner::Threading;
+use crate::stats::Summary;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+/// Name of the JSON report in a session directory.
+pub const REPORT_FILE: &str = "report.json";
+
+/// Name of the CSV file with one row per run.
+pub const RUNS_CSV: &str = "runs.csv";
+
+/// Name of the CSV file with one row per executable.
+pub const SUMMARY_CSV: &str = "summary.csv";
+
+/// Name of the plain-text leaderboard in a session directory.
+pub const LEADERBOARD_FILE: &str = "leaderboard.txt";
+
+/// Name of the JSON file with the curve and fitted complexity measured by `scale`.
+pub const SCALE_JSON: &str = "scale.json";
+
+/// Name of the CSV file with the curve measured by `scale`.
+pub const SCALE_CSV: &str = "scale.csv";
+
+/// Everything a benchmark session measured, and what it needs to be reproduced.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionReport {
+    pub challenge: String,
+    /// Version of the challenge's reference implementation.
+    pub challenge_version: u32,
+    /// Every input parameter, overridden or default.
+    pub params: BTreeMap<String, u128>,
+    /// Seed of the input, or of the first run's input with fresh inputs per run.
+    pub seed: u64,
+    /// `session` or `run` if inputs were generated from fresh seeds.
+    pub fresh_inputs: Option<String>,
+    /// Runs before the measured ones, left out of the statistics.
+    pub warmup_runs: u32,
+    pub time_limit_secs: f64,
+    pub memory_limit_bytes: Option<u64>,
+    /// When the session started, in seconds since the Unix epoch.
+    pub started_at: u64,
+    /// Median in-process time of the reference implementation on the first input.
+    #[serde(default)]
+    pub reference_time_secs: Option<f64>,
+    pub executables: Vec<ExecutableReport>,
+}
+
+/// One executable's runs and statistics.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExecutableReport {
+    /// File name of the executable.
+    pub name: String,
+    pub path: String,
+    /// SHA-256 of the executable, if it could be read.
+    pub sha256: Option<String>,
+    /// Whether every run, warmups included, was accepted.
+    pub all_accepted: bool,
+    /// Every run in order, warmups first.
+    pub runs: Vec<RunRecord>,
+    /// Wall-clock time statistics of the measured runs, if there were any.
+    pub wall_time_secs: Option<Summary>,
+    /// Whether the measured runs used more than one core, if their CPU time was
+    /// measured.
+    #[serde(default)]
+    pub threading: Option<Threading>,
+}
+
+/// One run of an executable.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunRecord {
+    /// Run number, counted separately for warmups and measured runs.
+    pub run: u32,
+    pub warmup: bool,
+    /// Seed of the input the run got.
+    pub seed: u64,
+    /// Short verdict code, e.g. `AC` or `WA`.
+    pub verdict: String,
+    /// Human-readable verdict, e.g. `Runtime error (exit code 1)`.
+    pub verdict_detail: String,
+    pub accepted: bool,
+    pub wall_time_secs: f64,
+    /// Resource usage, where the operating system reports it.
+    pub peak_rss_bytes: Option<u64>,
+    pub user_cpu_secs: Option<f64>,
+    pub system_cpu_secs: Option<f64>,
+    pub voluntary_context_switches: Option<u64>,
+    pub involuntary_context_switches: Option<u64>,
+}
+
+impl SessionReport {
+    /// Writes the JSON report, both CSV files and the leaderboard into `session_dir`.
+    pub fn save(&self, session_dir: &Path) -> Result<(), Box<dyn Error>> {
+        fs::create_dir_all(session_dir)?;
+        fs::write(
+            session_dir.join(REPORT_FILE),
+            serde_json::to_string_pretty(self)?,
+        )?;
+        fs::write(session_dir.join(RUNS_CSV), self.runs_csv())?;
+        fs::write(session_dir.join(SUMMARY_CSV), self.summary_csv())?;
+        fs::write(session_dir.join(LEADERBOARD_FILE), self.leaderboard_table())?;
+        Ok(())
+    }
+
+    /// Reads the JSON report of the session in `session_dir`.
+    pub fn load(session_dir: &Path) -> Result<Self, Box<dyn Error>> {
+        let path = session_dir.join(REPORT_FILE);
+        let text = fs::read_to_string(&path)
+            .map_err(|err| format!("failed to read {}: {err}", path.display()))?;
+        serde_json::from_str(&text)
+            .map_err(|err| format!("failed to parse {}: {err}", path.display()).into())
+    }
+
+    /// One row per run of every executable.
+    pub fn runs_csv(&self) -> String {
+        let mut csv = String::from(
+            "executable,sha256,run,warmup,seed,verdict,verdict_detail,wall_time_secs,\
+             peak_rss_bytes,user_cpu_secs,system_cpu_secs,voluntary_context_switches,\
+             involuntary_context_switches\n",
+        );
+        for executable in &self.executables {
+            for run in &executable.runs {
+                push_row(
+                    &mut csv,
+                    &[
+                        executable.name.clone(),
+                        executable.sha256.clone().unwrap_or_default(),
+                        run.run.to_string(),
+                        run.warmup.to_string(),
+                        run.seed.to_string(),
+                        run.verdict.clone(),
+                        run.verdict_detail.clone(),
+                        run.wall_time_secs.to_string(),
+                        optional(run.peak_rss_bytes),
+                        optional(run.user_cpu_secs),
+                        optional(run.system_cpu_secs),
+                        optional(run.voluntary_context_switches),
+                        optional(run.involuntary_context_switches),
+                    ],
+                );
+            }
+        }
+        csv
+    }
+
+    /// One row per executable with its wall-clock time statistics in seconds.
+    pub fn summary_csv(&self) -> String {
+        let mut csv = String::from(
+            "executable,sha256,all_accepted,threading,runs,mean,std_dev,min,max,median,p90,p99,mad,\
+             mean_ci_low,mean_ci_high,median_ci_low,median_ci_high,outliers\n",
+        );
+        for executable in &self.executables {
+            let mut row = vec![
+                executable.name.clone(),
+                executable.sha256.clone().unwrap_or_default(),
+                executable.all_accepted.to_string(),
+                optional(executable.threading),
+            ];
+            match &executable.wall_time_secs {
+                Some(stats) => row.extend(
+                    [
+                        stats.count as f64,
+                        stats.mean,
+                        stats.std_dev,
+                        stats.min,
+                        stats.max,
+                        stats.median,
+                        stats.p90,
+                        stats.p99,
+                        stats.mad,
+                        stats.mean_ci.low,
+                        stats.mean_ci.high,
+                        stats.median_ci.low,
+                        stats.median_ci.high,
+                        stats.outliers.len() as f64,
+                    ]
+                    .map(|value| value.to_string()),
+                ),
+                None => row.extend(std::iter::repeat_n(String::new(), 14)),
+            }
+            push_row(&mut csv, &row);
+        }
+        csv
+    }
+}
+
+/// One executable's place on the leaderboard.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Standing {
+    /// Single- and multi-threaded executables are ranked separately.
+    pub threading: Option<Threading>,
+    /// 1 for the fastest correct executable of its threading, shared by executables
+    /// with the same median; `None` for incorrect ones, which are listed after every
+    /// correct one.
+    pub rank: Option<usize>,
+    pub name: String,
+    /// Verdict counts, e.g. `AC 9, TLE 1`.
+    pub verdicts: String,
+    /// Median wall-clock time of the measured runs, if there were any.
+    pub median: Option<f64>,
+    /// Median divided by the median of the fastest correct executable of the same
+    /// threading; `None` for incorrect ones, whose times say nothing about a correct
+    /// solution.
+    pub vs_fastest: Option<f64>,
+    /// Median divided by the reference implementation's time; `None` for incorrect
+    /// executables.
+    pub vs_reference: Option<f64>,
+}
+
+impl Standing {
+    /// Heading of the group the executable is ranked in, e.g. `Single-threaded`.
+    pub fn category(&self) -> &'static str {
+        match self.threading {
+            Some(Threading::SingleThreaded) => "Single-threaded",
+            Some(Threading::MultiThreaded) => "Multi-threaded",
+            None => "Threading not measured",
+        }
+    }
+}
+
+impl ExecutableReport {
+    /// How often each verdict occurred, in order of first occurrence, e.g. `AC 9, TLE 1`.
+    pub fn verdict_counts(&self) -> String {
+        let mut counts: Vec<(&str, usize)> = Vec::new();
+        for run in &self.runs {
+            match counts.iter_mut().find(|(short, _)| *short == run.verdict) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((&run.verdict, 1)),
+            }
+        }
+        counts
+            .iter()
+            .map(|(short, count)| format!("{short} {count}"))
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
+
+    fn median(&self) -> Option<f64> {
+        self.wall_time_secs.as_ref().map(|stats| stats.median)
+    }
+}
+
+impl SessionReport {
+    /// The executables ranked by median time, correct ones first, single-threaded
+    /// ones ranked apart from multi-threaded ones.
+    pub fn leaderboard(&self) -> Vec<Standing> {
+        let group = |executable: &ExecutableReport| match executable.threading {
+            Some(Threading::SingleThreaded) => 0,
+            Some(Threading::MultiThreaded) => 1,
+            None => 2,
+        };
+        let mut executables: Vec<&ExecutableReport> = self.executables.iter().collect();
+        // By group, then correct first, then by median; executables without timings go
+        // last.
+        executables.sort_by(|a, b| {
+            group(a)
+                .cmp(&group(b))
+                .then_with(|| b.all_accepted.cmp(&a.all_accepted))
+                .then_with(|| {
+                    let a = a.median().unwrap_or(f64::INFINITY);
+                    let b = b.median().unwrap_or(f64::INFINITY);
+                    a.total_cmp(&b)
+                })
+        });
+        let mut current_group = None;
+        let mut fastest = None;
+        let mut position = 0;
+        // Median and rank of the previous correct executable, which ties share.
+        let mut previous: Option<(f64, usize)> = None;
+        executables
+            .into_iter()
+            .map(|executable| {
+                // The first executable of a group is its fastest correct one, if any.
+                if current_group != Some(group(executable)) {
+                    current_group = Some(group(executable));
+                    fastest = executable.median().filter(|_| executable.all_accepted);
+                    position = 0;
+                    previous = None;
+                }
+                let median = executable.median();
+                let ratio = |base: Option<f64>| match (median, base) {
+                    (Some(median), Some(base)) if executable.all_accepted && base > 0.0 => {
+                        Some(median / base)
+                    }
+                    _ => None,
+                };
+                Standing {
+                    threading: executable.threading,
+                    rank: executable.all_accepted.then(|| {
+                        position += 1;
+                        match previous {
+                            Some((previous_median, rank)) if median == Some(previous_median) => {
+                                rank
+                            }
+                            _ => {
+                                previous = median.map(|median| (median, position));
+                                position
+                            }
+                        }
+                    }),
+                    name: executable.name.clone(),
+                    verdicts: executable.verdict_counts(),
+                    median,
+                    vs_fastest: ratio(fastest),
+                    vs_reference: ratio(self.reference_time_secs),
+                }
+            })
+            .collect()
+    }
+
+    /// The leaderboard as a plain-text table.
+    pub fn leaderboard_table(&self) -> String {
+        let header = [
+            "Rank",
+            "Executable",
+            "Median",
+            "vs fastest",
+            "vs reference",
+            "Verdicts",
+        ]
+        .map(String::from);
+        let ratio = |ratio: Option<f64>| match ratio {
+            Some(ratio) => format!("{ratio:.2}x"),
+            None => String::from("-"),
+        };
+        let mut rows = vec![(None, header)];
+        let mut category = None;
+        for standing in self.leaderboard() {
+            // Start every group with its heading.
+            let heading = (category != Some(standing.category())).then(|| standing.category());
+            category = Some(standing.category());
+            rows.push((
+                heading,
+                [
+                    standing
+                        .rank
+                        .map_or_else(|| String::from("-"), |rank| rank.to_string()),
+                    standing.name,
+                    standing
+                        .median
+                        .map_or_else(|| String::from("-"), format_time),
+                    ratio(standing.vs_fastest),
+                    ratio(standing.vs_reference),
+                    standing.verdicts,
+                ],
+            ));
+        }
+        let widths: Vec<usize> = (0..rows[0].1.len())
+            .map(|column| {
+                rows.iter()
+                    .map(|(_, row)| row[column].chars().count())
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+        let mut table = format!(
+            "Leaderboard for {} (reference: {})\n",
+            self.challenge,
+            self.reference_time_secs
+                .map_or_else(|| String::from("not timed"), format_time)
+        );
+        for (heading, row) in rows {
+            if let Some(heading) = heading {
+                table.push_str(heading);
+                table.push('\n');
+            }
+            let cells: Vec<String> = row
+                .iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{cell:<width$}"))
+                .collect();
+            table.push_str(cells.join("  ").trim_end());
+            table.push('\n');
+        }
+        table
+    }
+}
+
+/// Time and memory of one executable at one input size.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScalePoint {
+    pub size: u128,
+    /// The parameter's value at this size; differs from `size` for bit widths.
+    pub param_value: u128,
+    pub median_time_secs: f64,
+    /// Highest peak of the runs at this size, or `None` if any of them was too short
+    /// to be measured.
+    pub peak_rss_bytes: Option<u64>,
+}
+
+/// What `scale` measured for one executable, and the complexity fitted to it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScaleReport {
+    pub challenge: String,
+    pub executable: String,
+    /// The parameter that was grown.
+    pub param: String,
+    /// Whether sizes are bit widths of the parameter rather than its values.
+    pub bits: bool,
+    /// The challenge's other parameters, which stayed at these values.
+    #[serde(default)]
+    pub fixed_params: BTreeMap<String, u128>,
+    pub seed: u64,
+    /// Runs at each size.
+    pub runs: u32,
+    /// When the curve was measured, in seconds since the Unix epoch.
+    pub recorded_at: u64,
+    pub points: Vec<ScalePoint>,
+    pub time_power_fit: Option<PowerFit>,
+    pub time_class_fit: Option<ClassFit>,
+    pub memory_power_fit: Option<PowerFit>,
+    pub memory_class_fit: Option<ClassFit>,
+}
+
+impl ScaleReport {
+    /// Writes the JSON report and the CSV curve into `dir`.
+    pub fn save(&self, dir: &Path) -> Result<(), Box<dyn Error>> {
+        fs::create_dir_all(dir)?;
+        fs::write(dir.join(SCALE_JSON), serde_json::to_string_pretty(self)?)?;
+        let mut csv = String::from("size,param_value,median_time_secs,peak_rss_bytes\n");
+        for point in &self.points {
+            push_row(
+                &mut csv,
+                &[
+                    point.size.to_string(),
+                    point.param_value.to_string(),
+                    point.median_time_secs.to_string(),
+                    optional(point.peak_rss_bytes),
+                ],
+            );
+        }
+        fs::write(dir.join(SCALE_CSV), csv)?;
+        Ok(())
+    }
+
+    /// Reads the JSON report of the `scale` run in `dir`.
+    pub fn load(dir: &Path) -> Result<Self, Box<dyn Error>> {
+        let path = dir.join(SCALE_JSON);
+        let text = fs::read_to_string(&path)
+            .map_err(|err| format!("failed to read {}: {err}", path.display()))?;
+        serde_json::from_str(&text)
+            .map_err(|err| format!("failed to parse {}: {err}", path.display()).into())
+    }
+
+    /// Whether `other` grew the same parameter with the others at the same values, so
+    /// the two curves can share axes.
+    pub fn same_series(&self, other: &ScaleReport) -> bool {
+        self.param == other.param
+            && self.bits == other.bits
+            && self.fixed_params == other.fixed_params
+    }
+
+    /// What the sizes of the curve are, e.g. `bits of max_value (pairs = 1,000)`.
+    pub fn series_label(&self) -> String {
+        let mut label = if self.bits {
+            format!("bits of {}", self.param)
+        } else {
+            self.param.clone()
+        };
+        if !self.fixed_params.is_empty() {
+            let fixed: Vec<String> = self
+                .fixed_params
+                .iter()
+                .map(|(name, value)| format!("{name} = {}", format_param_value(*value)))
+                .collect();
+            label.push_str(&format!(" ({})", fixed.join(", ")));
+        }
+        label
+    }
+
+    /// File name of the executable.
+    pub fn executable_name(&self) -> &str {
+        Path::new(&self.executable)
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or(&self.executable)
+    }
+}
+
+/// Appends one CSV row, quoting fields that need it.
+fn push_row(csv: &mut String, fields: &[String]) {
+    let fields: Vec<String> = fields
+        .iter()
+        .map(|field| {
+            if field.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field.clone()
+            }
+        })
+        .collect();
+    csv.push_str(&fields.join(","));
+    csv.push('\n');
+}
+
+/// An optional CSV field, empty if there is no value.
+fn optional<T: ToString>(value: Option<T>) -> String {
+    value.map(|value| value.to_string()).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stats::summarize;
+
+    /// An executable with one measured run per entry of `verdicts`, timed `median`
+    /// seconds each when accepted.
+    fn executable(
+        name: &str,
+        threading: Option<Threading>,
+        median: f64,
+        verdicts: &[&str],
+    ) -> ExecutableReport {
+        let runs: Vec<RunRecord> = verdicts
+            .iter()
+            .enumerate()
+            .map(|(i, &verdict)| RunRecord {
+                run: i as u32 + 1,
+                warmup: false,
+                seed: 1,
+                verdict: verdict.to_string(),
+                verdict_detail: verdict.to_string(),
+                accepted: verdict == "AC",
+                wall_time_secs: median,
+                peak_rss_bytes: None,
+                user_cpu_secs: None,
+                system_cpu_secs: None,
+                voluntary_context_switches: None,
+                involuntary_context_switches: None,
+            })
+            .collect();
+        let times: Vec<f64> = runs
+            .iter()
+            .filter(|run| run.accepted)
+            .map(|run| run.wall_time_secs)
+            .collect();
+        ExecutableReport {
+            name: name.to_string(),
+            path: format!("./{name}"),
+            sha256: None,
+            all_accepted: runs.iter().all(|run| run.accepted),
+            runs,
+            wall_time_secs: summarize(&times),
+            threading,
+        }
+    }
+
+    fn session(executables: Vec<ExecutableReport>) -> SessionReport {
+        SessionReport {
+            challenge: String::from("gcd"),
+            challenge_version: 1,
+            params: BTreeMap::new(),
+            seed: 1,
+            fresh_inputs: None,
+            warmup_runs: 0,
+            time_limit_secs: 10.0,
+            memory_limit_bytes: None,
+            started_at: 0,
+            reference_time_secs: Some(0.5),
+            executables,
+        }
+    }
+
+    const SINGLE: Option<Threading> = Some(Threading::SingleThreaded);
+    const MULTI: Option<Threading> = Some(Threading::MultiThreaded);
+
+    fn ranks(report: &SessionReport) -> Vec<(String, Option<usize>)> {
+        report
+            .leaderboard()
+            .into_iter()
+            .map(|standing| (standing.name, standing.rank))
+            .collect()
+    }
+
+    #[test]
+    fn ties_share_a_rank() {
+        let report = session(vec![
+            executable("c", SINGLE, 2.0, &["AC"]),
+            executable("a", SINGLE, 1.0, &["AC"]),
+            executable("b", SINGLE, 1.0, &["AC"]),
+        ]);
+        assert_eq!(
+            ranks(&report),
+            [
+                (String::from("a"), Some(1)),
+                (String::from("b"), Some(1)),
+                (String::from("c"), Some(3)),
+            ]
+        );
+        let leaderboard = report.leaderboard();
+        assert_eq!(leaderboard[1].vs_fastest, Some(1.0));
+        assert_eq!(leaderboard[2].vs_fastest, Some(2.0));
+        assert_eq!(leaderboard[2].vs_reference, Some(4.0));
+    }
+
+    #[test]
+    fn rejected_executables_come_last_without_rank_or_ratios() {
+        let report = session(vec![
+            executable("wrong", SINGLE, 0.1, &["AC", "WA"]),
+            executable("crashed", SINGLE, 0.0, &["RE", "RE"]),
+            executable("slow", SINGLE, 3.0, &["AC", "AC"]),
+        ]);
+        let leaderboard = report.leaderboard();
+        assert_eq!(
+            ranks(&report),
+            [
+                (String::from("slow"), Some(1)),
+                (String::from("wrong"), None),
+                (String::from("crashed"), None),
+            ]
+        );
+        assert_eq!(leaderboard[1].median, Some(0.1));
+        assert_eq!(leaderboard[1].vs_fastest, None);
+        assert_eq!(leaderboard[1].vs_reference, None);
+        assert_eq!(leaderboard[2].median, None);
+        assert_eq!(leaderboard[2].verdicts, "RE 2");
+
+        let table = report.leaderboard_table();
+        let row = table.lines().find(|line| line.contains("crashed")).unwrap();
+        assert_eq!(
+            row.split_whitespace().collect::<Vec<_>>(),
+            ["-", "crashed", "-", "-", "-", "RE", "2"]
+        );
+    }
+
+    #[test]
+    fn single_and_multi_threaded_are_ranked_separately() {
+        let report = session(vec![
+            executable("parallel", MULTI, 0.5, &["AC"]),
+            executable("serial", SINGLE, 2.0, &["AC"]),
+            executable("unmeasured", None, 1.0, &["AC"]),
+            executable("faster-serial", SINGLE, 1.0, &["AC"]),
+            executable("wrong-parallel", MULTI, 0.1, &["WA"]),
+        ]);
+        let leaderboard = report.leaderboard();
+        let categories: Vec<&str> = leaderboard.iter().map(Standing::category).collect();
+        assert_eq!(
+            categories,
+            [
+                "Single-threaded",
+                "Single-threaded",
+                "Multi-threaded",
+                "Multi-threaded",
+                "Threading not measured",
+            ]
+        );
+        assert_eq!(
+            ranks(&report),
+            [
+                (String::from("faster-serial"), Some(1)),
+                (String::from("serial"), Some(2)),
+                (String::from("parallel"), Some(1)),
+                (String::from("wrong-parallel"), None),
+                (String::from("unmeasured"), Some(1)),
+            ]
+        );
+        // Each group is compared with its own fastest executable.
+        assert_eq!(leaderboard[1].vs_fastest, Some(2.0));
+        assert_eq!(leaderboard[2].vs_fastest, Some(1.0));
+        assert_eq!(leaderboard[4].vs_fastest, Some(1.0));
+
+        let table = report.leaderboard_table();
+        let headings: Vec<&str> = table
+            .lines()
+            .filter(|line| line.ends_with("threaded") || line.ends_with("measured"))
+            .collect();
+        assert_eq!(
+            headings,
+            [
+                "Single-threaded",
+                "Multi-threaded",
+                "Threading not measured"
+            ]
+        );
+    }
+}
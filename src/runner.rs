@@ -0,0 +1,810 @@
+use crate::checker::Checker;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::io::{self, ErrorKind, Read, Write};
+use std::path::Path;
+use std::process::{Child, ChildStdin, Command, ExitStatus, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Online-judge style verdict for a single run of a submission.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    /// The process exited cleanly and produced the expected output.
+    Accepted,
+    /// The process exited cleanly but the output did not match.
+    WrongAnswer,
+    /// The process exited cleanly but printed something other than the number the
+    /// challenge asks for.
+    NotANumber,
+    /// The process exited with a non-zero code or was killed by a signal.
+    RuntimeError {
+        code: Option<i32>,
+        signal: Option<i32>,
+    },
+    /// The process did not finish within the time limit.
+    TimeLimitExceeded,
+    /// The process's resident memory grew beyond the memory limit, or it crashed
+    /// because the kernel refused to let it allocate more.
+    MemoryLimitExceeded,
+    /// The executable could not be started at all.
+    SpawnFailed(String),
+}
+
+impl Verdict {
+    /// Short judge-style abbreviation, e.g. `AC` or `RE`.
+    pub fn short(&self) -> &'static str {
+        match self {
+            Verdict::Accepted => "AC",
+            Verdict::WrongAnswer => "WA",
+            Verdict::NotANumber => "NaN",
+            Verdict::RuntimeError { .. } => "RE",
+            Verdict::TimeLimitExceeded => "TLE",
+            Verdict::MemoryLimitExceeded => "MLE",
+            Verdict::SpawnFailed(_) => "SF",
+        }
+    }
+
+    pub fn is_accepted(&self) -> bool {
+        matches!(self, Verdict::Accepted)
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Accepted => write!(f, "Accepted"),
+            Verdict::WrongAnswer => write!(f, "Wrong answer"),
+            Verdict::NotANumber => write!(f, "Not a number"),
+            Verdict::RuntimeError {
+                code: Some(code), ..
+            } => write!(f, "Runtime error (exit code {code})"),
+            Verdict::RuntimeError {
+                signal: Some(signal),
+                ..
+            } => write!(f, "Runtime error (killed by signal {signal})"),
+            Verdict::RuntimeError { .. } => write!(f, "Runtime error"),
+            Verdict::TimeLimitExceeded => write!(f, "Time limit exceeded"),
+            Verdict::MemoryLimitExceeded => write!(f, "Memory limit exceeded"),
+            Verdict::SpawnFailed(reason) => write!(f, "Failed to start: {reason}"),
+        }
+    }
+}
+
+/// How much of a child's stderr is kept; older output is dropped first.
+pub const STDERR_LIMIT: usize = 1024 * 1024;
+
+/// Longest gap between two samples of the child's memory by the watchdog.
+const MEMORY_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// How often the watchdog looks for new processes in the child's process group.
+const GROUP_SCAN_INTERVAL: Duration = Duration::from_millis(100);
+
+/// What common language runtimes print when an allocation fails. Under the kernel's
+/// cap on the data segment, a crash with one of these means the memory limit was hit.
+const OUT_OF_MEMORY_MESSAGES: [&str; 5] = [
+    "MemoryError",
+    "std::bad_alloc",
+    "memory allocation of",
+    "out of memory",
+    "OutOfMemoryError",
+];
+
+/// A crash with an out-of-memory message only counts as hitting the memory limit if
+/// the measured peak memory reached at least this fraction of it. The kernel's cap
+/// counts allocated rather than resident memory, so the peak can stay somewhat below
+/// the limit, but a program far below it ran out of memory for some other reason. A
+/// peak too small to be measured is far below any limit.
+const OUT_OF_MEMORY_PEAK_FRACTION: f64 = 0.5;
+
+/// Whether the running child's peak memory can be sampled on this platform.
+const CAN_SAMPLE_MEMORY: bool = cfg!(target_os = "linux");
+
+/// Resource limits applied to a single run.
+#[derive(Debug, Clone, Copy)]
+pub struct Limits {
+    /// Wall-clock time after which the process tree is killed.
+    pub time: Duration,
+    /// Peak resident memory, in bytes, after which the process tree is killed. The
+    /// kernel also caps each process's data segment at this size, so an allocation
+    /// past it fails even between two samples of the watchdog.
+    pub memory_bytes: Option<u64>,
+}
+
+/// CPU time per wall-clock time above which a run counts as multi-threaded.
+pub const MULTI_THREADED_CPU_RATIO: f64 = 1.2;
+
+/// CPU time beyond the wall-clock time that a multi-threaded run uses at least.
+/// Starting a process costs CPU time that is charged to the child but falls before
+/// its wall-clock time starts, which would otherwise dominate the ratio of very
+/// short runs.
+const MIN_PARALLEL_CPU_PER_RUN: Duration = Duration::from_millis(5);
+
+/// Resources consumed by a finished run, as reported by the operating system.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ResourceUsage {
+    /// Peak resident set size in bytes of the process and everything it spawned, or
+    /// `None` if the run was too short to be measured reliably.
+    pub peak_rss_bytes: Option<u64>,
+    /// CPU time spent in user mode, summed over all threads and child processes.
+    pub user_cpu: Duration,
+    /// CPU time spent in the kernel on behalf of the process.
+    pub system_cpu: Duration,
+    /// Context switches because the process blocked, e.g. waiting for I/O.
+    pub voluntary_context_switches: u64,
+    /// Context switches because the scheduler preempted the process.
+    pub involuntary_context_switches: u64,
+}
+
+impl ResourceUsage {
+    /// Total CPU time, user plus system.
+    pub fn cpu_time(&self) -> Duration {
+        self.user_cpu + self.system_cpu
+    }
+
+    /// CPU time divided by wall-clock time: about 1.0 for a busy single thread,
+    /// higher when several cores were used, lower when the process mostly waited.
+    pub fn cpu_wall_ratio(&self, elapsed: Duration) -> f64 {
+        if elapsed.is_zero() {
+            return 0.0;
+        }
+        self.cpu_time().as_secs_f64() / elapsed.as_secs_f64()
+    }
+}
+
+/// Whether a submission used more than one core, so it can be ranked separately.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Threading {
+    SingleThreaded,
+    MultiThreaded,
+}
+
+impl Threading {
+    /// Classifies a submission by the total CPU and wall-clock time of its `runs`.
+    pub fn classify(cpu: Duration, wall: Duration, runs: u32) -> Self {
+        let parallel_cpu = cpu.saturating_sub(wall);
+        if cpu.as_secs_f64() > MULTI_THREADED_CPU_RATIO * wall.as_secs_f64()
+            && parallel_cpu > MIN_PARALLEL_CPU_PER_RUN * runs
+        {
+            Threading::MultiThreaded
+        } else {
+            Threading::SingleThreaded
+        }
+    }
+}
+
+impl fmt::Display for Threading {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Threading::SingleThreaded => write!(f, "single-threaded"),
+            Threading::MultiThreaded => write!(f, "multi-threaded"),
+        }
+    }
+}
+
+/// Result of running an executable once.
+#[derive(Debug, Clone)]
+pub struct RunOutcome {
+    /// Everything the process wrote to stdout.
+    pub stdout: String,
+    /// The last [`STDERR_LIMIT`] bytes the process wrote to stderr.
+    pub stderr: String,
+    /// Whether earlier stderr output had to be dropped to stay within the limit.
+    pub stderr_truncated: bool,
+    /// Wall-clock time from the moment the process was started until it exited or
+    /// was killed. Excludes our own `fork`/`exec` overhead.
+    pub elapsed: Duration,
+    /// Resource usage of the process, where the platform can measure it.
+    pub usage: Option<ResourceUsage>,
+    /// Set when the run failed before its output could be judged.
+    pub failure: Option<Verdict>,
+}
+
+impl RunOutcome {
+    fn failed(verdict: Verdict, elapsed: Duration) -> Self {
+        RunOutcome {
+            stdout: String::new(),
+            stderr: String::new(),
+            stderr_truncated: false,
+            elapsed,
+            usage: None,
+            failure: Some(verdict),
+        }
+    }
+
+    /// Judges the run's output for `input` against `expected_output` using `checker`.
+    pub fn verdict(&self, checker: &Checker, input: &str, expected_output: &str) -> Verdict {
+        match &self.failure {
+            Some(verdict) => verdict.clone(),
+            None => checker.verdict(input, expected_output, &self.stdout),
+        }
+    }
+
+    /// The verdict for a run whose output was judged while it streamed in, given
+    /// whether it matched.
+    pub fn streamed_verdict(&self, output_matches: bool) -> Verdict {
+        match &self.failure {
+            Some(verdict) => verdict.clone(),
+            None if output_matches => Verdict::Accepted,
+            None => Verdict::WrongAnswer,
+        }
+    }
+}
+
+/// Runs an external executable by piping `input` to its stdin and capturing stdout and stderr.
+///
+/// Never panics on a misbehaving submission: failures to start, crashes and
+/// non-zero exit codes are reported through [`RunOutcome::failure`]. If the
+/// process is still running after the time limit, or its peak resident memory
+/// exceeds the memory limit, it and every process it spawned are killed and the
+/// run is reported as [`Verdict::TimeLimitExceeded`] or
+/// [`Verdict::MemoryLimitExceeded`]. A crash after an allocation was refused by the
+/// kernel's cap is reported as [`Verdict::MemoryLimitExceeded`] as well, if the peak
+/// memory came close to the limit.
+pub fn run_executable(path: &Path, input: &str, limits: &Limits) -> RunOutcome {
+    run(path, input, limits, None)
+}
+
+/// Like [`run_executable`], but streams stdout into `sink` as it arrives instead of
+/// capturing it, so memory stays bounded however much the process writes.
+/// [`RunOutcome::stdout`] is left empty.
+pub fn run_executable_streaming(
+    path: &Path,
+    input: &str,
+    limits: &Limits,
+    sink: &mut (dyn Write + Send),
+) -> RunOutcome {
+    run(path, input, limits, Some(sink))
+}
+
+fn run(
+    path: &Path,
+    input: &str,
+    limits: &Limits,
+    stdout_sink: Option<&mut (dyn Write + Send)>,
+) -> RunOutcome {
+    // The kernel may attribute our own peak memory to the child, see `ResourceUsage::new`.
+    let parent_peak_rss = memory_of("self").map(|(_, peak)| peak);
+    let mut command = Command::new(path);
+    command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    // Put the child in its own process group so the whole tree can be killed at once.
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(&mut command, 0);
+    #[cfg(unix)]
+    if let Some(limit) = limits.memory_bytes {
+        limit_data_segment(&mut command, limit);
+    }
+
+    let mut child = match command.spawn() {
+        Ok(child) => child,
+        Err(err) => {
+            return RunOutcome::failed(Verdict::SpawnFailed(err.to_string()), Duration::ZERO);
+        }
+    };
+    // `spawn` returns once the executable has been loaded, which is where its time starts.
+    let start = Instant::now();
+    let pid = child.id();
+    let child_stdin = child.stdin.take();
+    let mut child_stdout = child.stdout.take().expect("stdout is piped");
+    let child_stderr = child.stderr.take().expect("stderr is piped");
+
+    let (status, elapsed, (limit_verdict, sampled_peak_rss), stdout, stderr) =
+        thread::scope(|scope| {
+            let (cancel, cancelled) = mpsc::channel::<()>();
+            let watchdog = scope.spawn(move || watchdog(pid, limits, start, cancelled));
+            // Feed stdin and drain stdout and stderr on their own threads, so a solution
+            // that streams its answer can't deadlock against us on a full pipe.
+            scope.spawn(move || feed_stdin(child_stdin, input));
+            let reader = scope.spawn(move || {
+                let mut buf = Vec::new();
+                let _ = match stdout_sink {
+                    Some(sink) => io::copy(&mut child_stdout, sink).map(drop),
+                    None => child_stdout.read_to_end(&mut buf).map(drop),
+                };
+                buf
+            });
+            let stderr_reader = scope.spawn(move || read_tail(child_stderr, STDERR_LIMIT));
+
+            let status = wait_for_exit(&mut child);
+            let elapsed = start.elapsed();
+            drop(cancel);
+            let limit_verdict = watchdog.join().unwrap_or((None, None));
+            // Don't let background processes forked by the submission outlive the run.
+            // This also unblocks the feeder and reader if one of them held a pipe open.
+            #[cfg(unix)]
+            kill_process_tree(pid);
+            let stdout = reader.join().unwrap_or_default();
+            let stderr = stderr_reader.join().unwrap_or_default();
+            (status, elapsed, limit_verdict, stdout, stderr)
+        });
+
+    let (status, rusage) = match status {
+        Ok(result) => result,
+        Err(_) => {
+            let verdict = Verdict::RuntimeError {
+                code: None,
+                signal: None,
+            };
+            return RunOutcome::failed(verdict, elapsed);
+        }
+    };
+    let (stderr, stderr_truncated) = stderr;
+    let stderr = String::from_utf8_lossy(&stderr).into_owned();
+    let usage = rusage.map(|rusage| ResourceUsage::new(rusage, parent_peak_rss, sampled_peak_rss));
+    // The watchdog only samples memory periodically, so check the final figure too.
+    let over_memory = match (limits.memory_bytes, usage) {
+        (Some(limit), Some(usage)) => usage.peak_rss_bytes.is_some_and(|peak| peak > limit),
+        _ => false,
+    };
+    let near_memory_limit = match (limits.memory_bytes, usage.and_then(|u| u.peak_rss_bytes)) {
+        (Some(limit), Some(peak)) => peak as f64 >= OUT_OF_MEMORY_PEAK_FRACTION * limit as f64,
+        _ => false,
+    };
+    let out_of_memory = near_memory_limit
+        && OUT_OF_MEMORY_MESSAGES
+            .iter()
+            .any(|message| stderr.contains(message));
+    let failure = match limit_verdict {
+        Some(verdict) => Some(verdict),
+        None if over_memory => Some(Verdict::MemoryLimitExceeded),
+        None => runtime_error(status).map(|verdict| {
+            if out_of_memory {
+                Verdict::MemoryLimitExceeded
+            } else {
+                verdict
+            }
+        }),
+    };
+
+    RunOutcome {
+        stdout: String::from_utf8_lossy(&stdout).into_owned(),
+        stderr,
+        stderr_truncated,
+        elapsed,
+        usage,
+        failure,
+    }
+}
+
+/// Makes every process started by `command` fail to allocate more than `limit` bytes
+/// of private writable memory. Limits are inherited, so this covers grandchildren too.
+#[cfg(unix)]
+fn limit_data_segment(command: &mut Command, limit: u64) {
+    // `RLIMIT_DATA` counts anonymous mappings since Linux 4.7; elsewhere only the
+    // address space limit covers them.
+    #[cfg(target_os = "linux")]
+    let resource = libc::RLIMIT_DATA;
+    #[cfg(not(target_os = "linux"))]
+    let resource = libc::RLIMIT_AS;
+    let rlimit = libc::rlimit {
+        rlim_cur: limit as libc::rlim_t,
+        rlim_max: limit as libc::rlim_t,
+    };
+    // SAFETY: the closure runs between `fork` and `exec` and only calls `setrlimit`,
+    // which is async-signal-safe.
+    unsafe {
+        std::os::unix::process::CommandExt::pre_exec(command, move || {
+            if libc::setrlimit(resource, &rlimit) != 0 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(())
+        });
+    }
+}
+
+/// Kills the process tree once it exceeds `limits`, unless `cancelled` fires first.
+///
+/// Returns the verdict for the limit that was hit, if any, and the highest memory
+/// observed in the child's process group while it was running.
+fn watchdog(
+    pid: u32,
+    limits: &Limits,
+    start: Instant,
+    cancelled: Receiver<()>,
+) -> (Option<Verdict>, Option<u64>) {
+    // A limit too far in the future to represent is no limit at all.
+    let deadline = start.checked_add(limits.time);
+    let mut group = ProcessGroup::new(pid);
+    let mut sampled_peak = None;
+    // Sample early so short runs get measured too, then back off to the regular interval.
+    let mut poll_interval = Duration::from_millis(1);
+    loop {
+        let remaining = deadline.map_or(Duration::MAX, |deadline| {
+            deadline.saturating_duration_since(Instant::now())
+        });
+        if remaining.is_zero() {
+            kill_process_tree(pid);
+            return (Some(Verdict::TimeLimitExceeded), sampled_peak);
+        }
+        let wait = if CAN_SAMPLE_MEMORY {
+            remaining.min(poll_interval)
+        } else {
+            remaining
+        };
+        if cancelled.recv_timeout(wait) != Err(RecvTimeoutError::Timeout) {
+            return (None, sampled_peak);
+        }
+        poll_interval = (poll_interval * 2).min(MEMORY_POLL_INTERVAL);
+
+        if let Some(memory) = group.memory() {
+            sampled_peak = sampled_peak.max(Some(memory));
+            if limits.memory_bytes.is_some_and(|limit| memory > limit) {
+                kill_process_tree(pid);
+                return (Some(Verdict::MemoryLimitExceeded), sampled_peak);
+            }
+        }
+    }
+}
+
+/// Waits for the child to exit, collecting its resource usage where supported.
+#[cfg(unix)]
+fn wait_for_exit(child: &mut Child) -> io::Result<(ExitStatus, Option<libc::rusage>)> {
+    use std::os::unix::process::ExitStatusExt;
+
+    let pid = child.id() as libc::pid_t;
+    let mut status = 0;
+    // SAFETY: `rusage` is plain old data, so all-zeroes is a valid value.
+    let mut rusage: libc::rusage = unsafe { std::mem::zeroed() };
+    loop {
+        // SAFETY: both out-pointers are valid for writes for the duration of the call.
+        let ret = unsafe { libc::wait4(pid, &mut status, 0, &mut rusage) };
+        if ret == pid {
+            break;
+        }
+        let err = io::Error::last_os_error();
+        if err.kind() != ErrorKind::Interrupted {
+            return Err(err);
+        }
+    }
+    Ok((ExitStatus::from_raw(status), Some(rusage)))
+}
+
+/// Stand-in for `libc::rusage` on platforms without `wait4`.
+#[cfg(not(unix))]
+enum NoRusage {}
+
+/// Waits for the child to exit, collecting its resource usage where supported.
+#[cfg(not(unix))]
+fn wait_for_exit(child: &mut Child) -> io::Result<(ExitStatus, Option<NoRusage>)> {
+    child.wait().map(|status| (status, None))
+}
+
+#[cfg(unix)]
+impl ResourceUsage {
+    /// Builds the usage report from `wait4` results.
+    ///
+    /// On Linux, `ru_maxrss` of a freshly executed child starts out at the high-water
+    /// mark of the process that spawned it, so it only describes the child once it
+    /// exceeds `parent_peak_rss`. Below that, only the watchdog's samples are used, and
+    /// a run too short to be sampled has no peak at all rather than our own.
+    fn new(
+        rusage: libc::rusage,
+        parent_peak_rss: Option<u64>,
+        sampled_peak_rss: Option<u64>,
+    ) -> Self {
+        // `ru_maxrss` is in bytes on macOS and in kilobytes everywhere else.
+        #[cfg(target_os = "macos")]
+        let maxrss = rusage.ru_maxrss as u64;
+        #[cfg(not(target_os = "macos"))]
+        let maxrss = rusage.ru_maxrss as u64 * 1024;
+
+        let maxrss = match parent_peak_rss {
+            Some(parent) if maxrss <= parent => None,
+            _ => Some(maxrss),
+        };
+        ResourceUsage {
+            peak_rss_bytes: maxrss.max(sampled_peak_rss),
+            user_cpu: timeval_to_duration(rusage.ru_utime),
+            system_cpu: timeval_to_duration(rusage.ru_stime),
+            voluntary_context_switches: rusage.ru_nvcsw as u64,
+            involuntary_context_switches: rusage.ru_nivcsw as u64,
+        }
+    }
+}
+
+#[cfg(unix)]
+fn timeval_to_duration(tv: libc::timeval) -> Duration {
+    Duration::from_secs(tv.tv_sec as u64) + Duration::from_micros(tv.tv_usec as u64)
+}
+
+#[cfg(not(unix))]
+impl ResourceUsage {
+    fn new(rusage: NoRusage, _: Option<u64>, _: Option<u64>) -> Self {
+        match rusage {}
+    }
+}
+
+/// The processes in a child's process group, as seen by the watchdog.
+struct ProcessGroup {
+    leader: u32,
+    /// Members found by the last scan, which may include the leader.
+    members: Vec<u32>,
+    scanned_at: Option<Instant>,
+}
+
+impl ProcessGroup {
+    fn new(leader: u32) -> Self {
+        ProcessGroup {
+            leader,
+            members: Vec::new(),
+            scanned_at: None,
+        }
+    }
+
+    /// Memory held by the group: the larger of its members' summed resident memory
+    /// right now and the highest peak of any single member. `None` once the leader
+    /// has exited.
+    fn memory(&mut self) -> Option<u64> {
+        let (mut total, mut peak) = memory_of(&self.leader.to_string())?;
+        // Scanning every process is too costly to do on each sample.
+        if self
+            .scanned_at
+            .is_none_or(|scanned_at| scanned_at.elapsed() >= GROUP_SCAN_INTERVAL)
+        {
+            self.members = group_members(self.leader);
+            self.scanned_at = Some(Instant::now());
+        }
+        for member in self.members.iter().filter(|&&member| member != self.leader) {
+            if let Some((rss, member_peak)) = memory_of(&member.to_string()) {
+                total += rss;
+                peak = peak.max(member_peak);
+            }
+        }
+        Some(total.max(peak))
+    }
+}
+
+/// Current and peak resident set size in bytes of a process under `/proc`, e.g.
+/// `"self"` or a pid.
+#[cfg(target_os = "linux")]
+fn memory_of(proc_entry: &str) -> Option<(u64, u64)> {
+    let status = std::fs::read_to_string(format!("/proc/{proc_entry}/status")).ok()?;
+    let field = |name: &str| -> Option<u64> {
+        let line = status.lines().find(|line| line.starts_with(name))?;
+        let kilobytes: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+        Some(kilobytes * 1024)
+    };
+    Some((field("VmRSS:")?, field("VmHWM:")?))
+}
+
+/// Current and peak resident set size in bytes of a process under `/proc`, e.g.
+/// `"self"` or a pid.
+#[cfg(not(target_os = "linux"))]
+fn memory_of(_proc_entry: &str) -> Option<(u64, u64)> {
+    None
+}
+
+/// Pids of the processes in process group `group`.
+#[cfg(target_os = "linux")]
+fn group_members(group: u32) -> Vec<u32> {
+    let Ok(entries) = std::fs::read_dir("/proc") else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| entry.file_name().to_str()?.parse::<u32>().ok())
+        .filter(|pid| {
+            // The process group is the third field after the parenthesised command
+            // name, which may itself contain spaces and parentheses.
+            std::fs::read_to_string(format!("/proc/{pid}/stat")).is_ok_and(|stat| {
+                stat.rsplit_once(')')
+                    .and_then(|(_, fields)| fields.split_whitespace().nth(2)?.parse::<u32>().ok())
+                    == Some(group)
+            })
+        })
+        .collect()
+}
+
+/// Pids of the processes in process group `group`.
+#[cfg(not(target_os = "linux"))]
+fn group_members(_group: u32) -> Vec<u32> {
+    Vec::new()
+}
+
+/// Writes all of `input` to the child's stdin, then closes it so the child sees EOF.
+fn feed_stdin(child_stdin: Option<ChildStdin>, input: &str) {
+    let Some(mut child_stdin) = child_stdin else {
+        return;
+    };
+    // A process that exits without reading all of its input closes the pipe;
+    // that is not our error, so let the exit status decide the verdict.
+    match child_stdin.write_all(input.as_bytes()) {
+        Ok(()) => {}
+        Err(err) if err.kind() == ErrorKind::BrokenPipe => {}
+        Err(err) => eprintln!("Warning: failed to write input to child process: {err}"),
+    }
+}
+
+/// Reads `source` to the end, keeping only the last `limit` bytes.
+///
+/// Returns the kept bytes and whether anything was dropped.
+fn read_tail(mut source: impl Read, limit: usize) -> (Vec<u8>, bool) {
+    let mut kept = Vec::new();
+    let mut truncated = false;
+    let mut chunk = [0u8; 8192];
+    loop {
+        match source.read(&mut chunk) {
+            Ok(0) => break,
+            Ok(n) => kept.extend_from_slice(&chunk[..n]),
+            Err(err) if err.kind() == ErrorKind::Interrupted => continue,
+            Err(_) => break,
+        }
+        // Trim in batches so a chatty process doesn't cost a shift per read.
+        if kept.len() > 2 * limit {
+            kept.drain(..kept.len() - limit);
+            truncated = true;
+        }
+    }
+    if kept.len() > limit {
+        kept.drain(..kept.len() - limit);
+        truncated = true;
+    }
+    (kept, truncated)
+}
+
+/// Kills the process group led by `pid`: the child and everything it forked.
+#[cfg(unix)]
+fn kill_process_tree(pid: u32) {
+    // SAFETY: `kill` has no memory-safety preconditions. A negative pid targets
+    // the process group, which `process_group(0)` made equal to the child's pid.
+    unsafe {
+        libc::kill(-(pid as libc::pid_t), libc::SIGKILL);
+    }
+}
+
+/// Kills the process `pid` together with its child processes.
+#[cfg(not(unix))]
+fn kill_process_tree(pid: u32) {
+    let _ = Command::new("taskkill")
+        .args(["/F", "/T", "/PID", &pid.to_string()])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status();
+}
+
+/// Maps an unsuccessful exit status to a [`Verdict::RuntimeError`].
+fn runtime_error(status: ExitStatus) -> Option<Verdict> {
+    if status.success() {
+        return None;
+    }
+    #[cfg(unix)]
+    let signal = std::os::unix::process::ExitStatusExt::signal(&status);
+    #[cfg(not(unix))]
+    let signal = None;
+    Some(Verdict::RuntimeError {
+        code: status.code(),
+        signal,
+    })
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+    use std::path::PathBuf;
+
+    /// An executable shell script in the temp directory, removed when dropped.
+    struct Script(PathBuf);
+
+    impl Drop for Script {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.0);
+        }
+    }
+
+    /// Writes `body` as an executable shell script named `name`.
+    fn script(name: &str, body: &str) -> Script {
+        let path = std::env::temp_dir().join(format!("runner-test-{}-{name}", std::process::id()));
+        std::fs::write(&path, format!("#!/bin/sh\n{body}\n")).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        Script(path)
+    }
+
+    fn limits(time: Duration) -> Limits {
+        Limits {
+            time,
+            memory_bytes: None,
+        }
+    }
+
+    #[test]
+    fn timeout_kills_the_whole_process_group() {
+        // The background `sleep` holds stdout open, so the run can only end in time if
+        // it is killed together with the shell.
+        let path = script("timeout", "sleep 30 & sleep 30");
+        let started = Instant::now();
+        let outcome = run_executable(&path.0, "", &limits(Duration::from_millis(300)));
+        assert_eq!(outcome.failure, Some(Verdict::TimeLimitExceeded));
+        assert!(started.elapsed() < Duration::from_secs(10));
+    }
+
+    #[test]
+    fn background_processes_do_not_outlive_the_run() {
+        let path = script("background", "sleep 30 &\necho 42");
+        let started = Instant::now();
+        let outcome = run_executable(&path.0, "", &limits(Duration::from_secs(10)));
+        assert_eq!(outcome.failure, None);
+        assert_eq!(outcome.stdout, "42\n");
+        assert!(started.elapsed() < Duration::from_secs(10));
+    }
+
+    #[test]
+    fn child_that_ignores_its_input_is_judged_by_its_exit_status() {
+        // Far more input than fits in a pipe, so feeding it fails with a broken pipe.
+        let input = "1 2\n".repeat(1 << 20);
+        let path = script("ignores-input", "echo 3");
+        let outcome = run_executable(&path.0, &input, &limits(Duration::from_secs(10)));
+        assert_eq!(outcome.failure, None);
+        assert_eq!(outcome.stdout, "3\n");
+
+        let path = script("ignores-input-and-fails", "exit 4");
+        let outcome = run_executable(&path.0, &input, &limits(Duration::from_secs(10)));
+        assert_eq!(
+            outcome.failure,
+            Some(Verdict::RuntimeError {
+                code: Some(4),
+                signal: None
+            })
+        );
+    }
+
+    #[test]
+    fn large_stdout_and_stderr_do_not_deadlock() {
+        // Fill stderr first: a runner that only drained stdout would wait forever.
+        let path = script(
+            "chatty",
+            "head -c 3000000 /dev/zero | tr '\\0' e >&2\nhead -c 3000000 /dev/zero | tr '\\0' o",
+        );
+        let outcome = run_executable(&path.0, "", &limits(Duration::from_secs(30)));
+        assert_eq!(outcome.failure, None);
+        assert_eq!(outcome.stdout.len(), 3_000_000);
+        assert!(outcome.stdout.bytes().all(|byte| byte == b'o'));
+        assert_eq!(outcome.stderr.len(), STDERR_LIMIT);
+        assert!(outcome.stderr.bytes().all(|byte| byte == b'e'));
+        assert!(outcome.stderr_truncated);
+    }
+
+    #[test]
+    fn read_tail_keeps_the_last_bytes() {
+        assert_eq!(read_tail(&b"hello"[..], 10), (b"hello".to_vec(), false));
+        assert_eq!(read_tail(&b"hello"[..], 5), (b"hello".to_vec(), false));
+        assert_eq!(read_tail(&b"hello world"[..], 5), (b"world".to_vec(), true));
+        assert_eq!(read_tail(&b""[..], 5), (Vec::new(), false));
+        // Longer than one read and than twice the limit.
+        let source: Vec<u8> = (0..100_000u32).map(|i| (i % 251) as u8).collect();
+        let (kept, truncated) = read_tail(&source[..], 1000);
+        assert_eq!(kept, source[source.len() - 1000..]);
+        assert!(truncated);
+    }
+
+    #[test]
+    fn runtime_error_reports_exit_code_and_signal() {
+        let status = |command: &str| Command::new("sh").args(["-c", command]).status().unwrap();
+        assert_eq!(runtime_error(status("exit 0")), None);
+        let verdict = runtime_error(status("exit 3")).unwrap();
+        assert_eq!(
+            verdict,
+            Verdict::RuntimeError {
+                code: Some(3),
+                signal: None
+            }
+        );
+        assert_eq!(verdict.to_string(), "Runtime error (exit code 3)");
+        let verdict = runtime_error(status("kill -SEGV $$")).unwrap();
+        assert_eq!(
+            verdict,
+            Verdict::RuntimeError {
+                code: None,
+                signal: Some(libc::SIGSEGV)
+            }
+        );
+        assert_eq!(
+            verdict.to_string(),
+            format!("Runtime error (killed by signal {})", libc::SIGSEGV)
+        );
+    }
+}
@@ -0,0 +1,339 @@
+//! Summary statistics for run timings.
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+/// How many resamples the bootstrap confidence intervals use.
+const BOOTSTRAP_RESAMPLES: usize = 2_000;
+
+/// Fixed seed for the bootstrap, so the same timings always give the same interval.
+const BOOTSTRAP_SEED: u64 = 9001;
+
+/// Samples further than this many interquartile ranges outside the quartiles are
+/// flagged as outliers (Tukey's fences).
+const OUTLIER_IQR_FACTOR: f64 = 1.5;
+
+/// A closed interval, e.g. a confidence interval.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Interval {
+    pub low: f64,
+    pub high: f64,
+}
+
+impl Interval {
+    pub fn width(&self) -> f64 {
+        self.high - self.low
+    }
+}
+
+/// Descriptive statistics of a set of samples.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Summary {
+    pub count: usize,
+    pub mean: f64,
+    /// Population standard deviation.
+    pub std_dev: f64,
+    pub min: f64,
+    pub max: f64,
+    pub median: f64,
+    pub p90: f64,
+    pub p99: f64,
+    /// First and third quartiles.
+    pub q1: f64,
+    pub q3: f64,
+    /// Median absolute deviation from the median.
+    pub mad: f64,
+    /// Indices of the samples outside the interquartile fences.
+    pub outliers: Vec<usize>,
+    /// Bootstrap 95% confidence interval for the mean.
+    pub mean_ci: Interval,
+    /// Bootstrap 95% confidence interval for the median.
+    pub median_ci: Interval,
+}
+
+/// Summarises `samples`, or `None` if there are none.
+pub fn summarize(samples: &[f64]) -> Option<Summary> {
+    if samples.is_empty() {
+        return None;
+    }
+    let count = samples.len() as f64;
+    let mean = samples.iter().sum::<f64>() / count;
+    let variance = samples.iter().map(|&x| (x - mean).powi(2)).sum::<f64>() / count;
+    let ordered = sorted(samples);
+    let median = percentile(&ordered, 50.0);
+    let q1 = percentile(&ordered, 25.0);
+    let q3 = percentile(&ordered, 75.0);
+    let iqr = q3 - q1;
+    let (low_fence, high_fence) = (q1 - OUTLIER_IQR_FACTOR * iqr, q3 + OUTLIER_IQR_FACTOR * iqr);
+    let deviations: Vec<f64> = samples.iter().map(|&x| (x - median).abs()).collect();
+
+    Some(Summary {
+        count: samples.len(),
+        mean,
+        std_dev: variance.sqrt(),
+        min: ordered[0],
+        max: ordered[ordered.len() - 1],
+        median,
+        p90: percentile(&ordered, 90.0),
+        p99: percentile(&ordered, 99.0),
+        q1,
+        q3,
+        mad: percentile(&sorted(&deviations), 50.0),
+        outliers: samples
+            .iter()
+            .enumerate()
+            .filter(|&(_, &x)| x < low_fence || x > high_fence)
+            .map(|(i, _)| i)
+            .collect(),
+        mean_ci: bootstrap_ci(samples, |resample| {
+            resample.iter().sum::<f64>() / resample.len() as f64
+        }),
+        median_ci: median_ci(samples),
+    })
+}
+
+/// Bootstrap 95% confidence interval for the median of `samples`.
+pub fn median_ci(samples: &[f64]) -> Interval {
+    bootstrap_ci(samples, |resample| percentile(&sorted(resample), 50.0))
+}
+
+/// The median of `samples`.
+pub fn median(samples: &[f64]) -> f64 {
+    percentile(&sorted(samples), 50.0)
+}
+
+/// The `p`th percentile of `sorted`, interpolating linearly between samples.
+pub fn percentile(sorted: &[f64], p: f64) -> f64 {
+    let rank = p / 100.0 * (sorted.len() - 1) as f64;
+    let below = rank.floor() as usize;
+    let above = rank.ceil() as usize;
+    sorted[below] + (sorted[above] - sorted[below]) * (rank - below as f64)
+}
+
+fn sorted(samples: &[f64]) -> Vec<f64> {
+    let mut sorted = samples.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    sorted
+}
+
+/// Percentile bootstrap 95% confidence interval for `statistic` of `samples`.
+fn bootstrap_ci(samples: &[f64], statistic: impl Fn(&[f64]) -> f64) -> Interval {
+    let mut rng = StdRng::seed_from_u64(BOOTSTRAP_SEED);
+    let mut resample = vec![0.0; samples.len()];
+    let mut estimates: Vec<f64> = (0..BOOTSTRAP_RESAMPLES)
+        .map(|_| {
+            for slot in resample.iter_mut() {
+                *slot = samples[rng.random_range(0..samples.len())];
+            }
+            statistic(&resample)
+        })
+        .collect();
+    estimates.sort_by(f64::total_cmp);
+    Interval {
+        low: percentile(&estimates, 2.5),
+        high: percentile(&estimates, 97.5),
+    }
+}
+
+/// Result of a two-sided Mann–Whitney U test.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MannWhitney {
+    /// U statistic of the first sample: how often one of its values beats (is below)
+    /// one of the second's, ties counting half.
+    pub u: f64,
+    /// Two-sided p-value from the normal approximation with tie correction.
+    pub p_value: f64,
+}
+
+/// Mann–Whitney U test of whether `a` and `b` come from the same distribution,
+/// without assuming either is normal. `None` if either sample is empty.
+pub fn mann_whitney_u(a: &[f64], b: &[f64]) -> Option<MannWhitney> {
+    if a.is_empty() || b.is_empty() {
+        return None;
+    }
+    let (n1, n2) = (a.len() as f64, b.len() as f64);
+    let mut pooled: Vec<(f64, bool)> = a
+        .iter()
+        .map(|&x| (x, true))
+        .chain(b.iter().map(|&x| (x, false)))
+        .collect();
+    pooled.sort_by(|x, y| x.0.total_cmp(&y.0));
+
+    // Rank the pooled samples, giving tied values their average rank.
+    let mut rank_sum_a = 0.0;
+    let mut tie_term = 0.0;
+    let mut start = 0;
+    while start < pooled.len() {
+        let mut end = start;
+        while end + 1 < pooled.len() && pooled[end + 1].0 == pooled[start].0 {
+            end += 1;
+        }
+        let ties = (end - start + 1) as f64;
+        let rank = (start + end) as f64 / 2.0 + 1.0;
+        rank_sum_a += rank * pooled[start..=end].iter().filter(|x| x.1).count() as f64;
+        tie_term += ties.powi(3) - ties;
+        start = end + 1;
+    }
+
+    let u = rank_sum_a - n1 * (n1 + 1.0) / 2.0;
+    let n = n1 + n2;
+    let mean = n1 * n2 / 2.0;
+    let variance = n1 * n2 / 12.0 * ((n + 1.0) - tie_term / (n * (n - 1.0)));
+    let p_value = if variance <= 0.0 {
+        1.0
+    } else {
+        // Continuity correction: U moves in steps of one half.
+        let z = ((u - mean).abs() - 0.5).max(0.0) / variance.sqrt();
+        erfc(z / std::f64::consts::SQRT_2)
+    };
+    Some(MannWhitney {
+        u,
+        p_value: p_value.min(1.0),
+    })
+}
+
+/// Complementary error function, accurate to about 1e-7 (Numerical Recipes' `erfcc`).
+fn erfc(x: f64) -> f64 {
+    let z = x.abs();
+    let t = 1.0 / (1.0 + 0.5 * z);
+    let polynomial = -z * z - 1.265_512_23
+        + t * (1.000_023_68
+            + t * (0.374_091_96
+                + t * (0.096_784_18
+                    + t * (-0.186_288_06
+                        + t * (0.278_868_07
+                            + t * (-1.135_203_98
+                                + t * (1.488_515_87 + t * (-0.822_152_23 + t * 0.170_872_77))))))));
+    let result = t * polynomial.exp();
+    if x >= 0.0 { result } else { 2.0 - result }
+}
+
+/// Bootstrap 95% confidence interval for `median(numerator) / median(denominator)`,
+/// resampling both samples independently.
+pub fn median_ratio_ci(numerator: &[f64], denominator: &[f64]) -> Interval {
+    let mut rng = StdRng::seed_from_u64(BOOTSTRAP_SEED);
+    let mut resample_numerator = vec![0.0; numerator.len()];
+    let mut resample_denominator = vec![0.0; denominator.len()];
+    let mut estimates: Vec<f64> = (0..BOOTSTRAP_RESAMPLES)
+        .map(|_| {
+            for slot in resample_numerator.iter_mut() {
+                *slot = numerator[rng.random_range(0..numerator.len())];
+            }
+            for slot in resample_denominator.iter_mut() {
+                *slot = denominator[rng.random_range(0..denominator.len())];
+            }
+            median(&resample_numerator) / median(&resample_denominator)
+        })
+        .collect();
+    estimates.sort_by(f64::total_cmp);
+    Interval {
+        low: percentile(&estimates, 2.5),
+        high: percentile(&estimates, 97.5),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+        assert!(
+            (actual - expected).abs() <= tolerance,
+            "expected {expected}, got {actual}"
+        );
+    }
+
+    #[test]
+    fn median_of_odd_and_even_counts() {
+        assert_eq!(median(&[3.0, 1.0, 2.0]), 2.0);
+        assert_eq!(median(&[4.0, 1.0, 3.0, 2.0]), 2.5);
+        assert_eq!(median(&[7.0]), 7.0);
+    }
+
+    #[test]
+    fn percentile_interpolates_between_samples() {
+        let sorted = [1.0, 2.0, 3.0, 4.0, 5.0];
+        assert_eq!(percentile(&sorted, 0.0), 1.0);
+        assert_eq!(percentile(&sorted, 25.0), 2.0);
+        assert_close(percentile(&sorted, 90.0), 4.6, 1e-12);
+        assert_eq!(percentile(&sorted, 100.0), 5.0);
+        assert_close(percentile(&[1.0, 2.0, 3.0, 4.0], 75.0), 3.25, 1e-12);
+    }
+
+    #[test]
+    fn tukey_fences_flag_samples_on_both_sides() {
+        // Quartiles 11.25 and 15.75, so the fences are at 4.5 and 22.5.
+        let samples = [10.0, 11.0, 12.0, 13.0, 100.0, 14.0, 15.0, 16.0, 17.0, -50.0];
+        let summary = summarize(&samples).unwrap();
+        assert_close(summary.q1, 11.25, 1e-12);
+        assert_close(summary.q3, 15.75, 1e-12);
+        assert_eq!(summary.outliers, vec![4, 9]);
+
+        let summary = summarize(&[1.0, 2.0, 3.0, 4.0, 5.0]).unwrap();
+        assert!(summary.outliers.is_empty());
+    }
+
+    #[test]
+    fn summary_of_identical_samples() {
+        let summary = summarize(&[2.0; 5]).unwrap();
+        assert_eq!(summary.std_dev, 0.0);
+        assert_eq!(summary.mad, 0.0);
+        assert_eq!(
+            summary.median_ci,
+            Interval {
+                low: 2.0,
+                high: 2.0
+            }
+        );
+        assert!(summarize(&[]).is_none());
+    }
+
+    #[test]
+    fn mann_whitney_without_ties() {
+        // Completely separated samples of five: U = 0, p ≈ 0.0122 with continuity
+        // correction.
+        let a = [1.0, 2.0, 3.0, 4.0, 5.0];
+        let b = [6.0, 7.0, 8.0, 9.0, 10.0];
+        let result = mann_whitney_u(&a, &b).unwrap();
+        assert_eq!(result.u, 0.0);
+        assert_close(result.p_value, 0.012_185_78, 1e-6);
+        // The test is symmetric: U of the other sample is n1 * n2 - U.
+        let reversed = mann_whitney_u(&b, &a).unwrap();
+        assert_eq!(reversed.u, 25.0);
+        assert_close(reversed.p_value, result.p_value, 1e-12);
+    }
+
+    #[test]
+    fn mann_whitney_with_ties() {
+        let a = [1.1, 2.2, 3.3, 4.4, 5.0, 5.0];
+        let b = [3.0, 5.0, 6.1, 7.2, 8.3, 9.4, 10.0];
+        let result = mann_whitney_u(&a, &b).unwrap();
+        assert_eq!(result.u, 5.0);
+        assert_close(result.p_value, 0.025_977_14, 1e-6);
+    }
+
+    #[test]
+    fn mann_whitney_of_identical_samples() {
+        let result = mann_whitney_u(&[1.0, 1.0, 1.0], &[1.0, 1.0]).unwrap();
+        assert_eq!(result.p_value, 1.0);
+        assert!(mann_whitney_u(&[], &[1.0]).is_none());
+    }
+
+    #[test]
+    fn erfc_at_known_points() {
+        assert_close(erfc(0.0), 1.0, 1e-7);
+        assert_close(erfc(0.5), 0.479_500_122, 1e-7);
+        assert_close(erfc(1.0), 0.157_299_207, 1e-7);
+        assert_close(erfc(-1.0), 1.842_700_793, 1e-7);
+        assert_close(erfc(2.0), 0.004_677_735, 1e-8);
+    }
+
+    #[test]
+    fn median_ratio_ci_contains_the_ratio() {
+        let denominator = [1.0, 1.1, 0.9, 1.05, 0.95, 1.0, 1.02];
+        let numerator: Vec<f64> = denominator.iter().map(|x| 2.0 * x).collect();
+        let interval = median_ratio_ci(&numerator, &denominator);
+        assert!(interval.low <= 2.0 && 2.0 <= interval.high, "{interval:?}");
+    }
+}